@@ -0,0 +1,38 @@
+//! A rough measurement of how long it takes to build, invalidate and recompute a graph with many
+//! signals that only have a single dependent each. Run with `cargo bench --bench signals`.
+
+use std::{hint::black_box, time::Instant};
+
+use fluorine::{Dependent, Rx, RxFn};
+
+const SIGNALS: usize = 10_000;
+const ROUNDS: usize = 100;
+
+fn main() {
+    let start = Instant::now();
+
+    for _ in 0..ROUNDS {
+        let mut signals: Vec<_> = (0..SIGNALS).map(Rx::new).collect();
+        let mut memos: Vec<RxFn<(), usize>> = (0..SIGNALS).map(|_| RxFn::new()).collect();
+        // one toplevel per memo so that every node in the graph has a single dependent
+        let toplevels: Vec<_> = (0..SIGNALS).map(|_| Dependent::toplevel()).collect();
+
+        for ((signal, memo), toplevel) in signals.iter().zip(&mut memos).zip(&toplevels) {
+            black_box(memo.call(&toplevel.ctx(), (), |ctx, ()| *signal.get(ctx) + 1));
+        }
+
+        for signal in &mut signals {
+            *signal.get_mut() += 1;
+        }
+
+        for ((signal, memo), toplevel) in signals.iter().zip(&mut memos).zip(&toplevels) {
+            black_box(memo.call(&toplevel.ctx(), (), |ctx, ()| *signal.get(ctx) + 1));
+        }
+    }
+
+    println!(
+        "{} signals: {:?} per round",
+        SIGNALS,
+        start.elapsed() / ROUNDS as u32,
+    );
+}
@@ -1,7 +1,7 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // hide console window on Windows in release
 #![allow(rustdoc::missing_crate_level_docs)] // it's an example
 
-use std::{borrow::Cow, cell::RefCell, rc::Rc};
+use std::{borrow::Cow, rc::Rc};
 
 use eframe::egui;
 use fluorine::*;
@@ -20,7 +20,7 @@ fn main() -> Result<(), eframe::Error> {
     )
 }
 
-type Cells = [(Rc<str>, Rx<Option<Expr>>, RefCell<RxFn<(), Option<f64>>>); 4];
+type Cells = [(Rc<str>, Rx<Option<Expr>>, RxFnCell<(), Option<f64>>); 4];
 
 struct Spreasheet {
     dependent: Rc<Dependent>,
@@ -31,18 +31,13 @@ impl Spreasheet {
     fn eval_cell(&self, ctx: &RxCtx, i: usize) -> Option<f64> {
         let cell = &self.cells.get(i)?;
 
-        let Ok(mut rx_fn) = cell.2.try_borrow_mut() else {
-            // If can't get a lock on the RxFn because we are being evaluated by it due to a cycle
-            // we track its Expr as a fallback so that reactivity doesn't get lost. Otherwise if the
-            // cell changes in a way that would break the cycle we wouldn't get invalidated.
-            cell.1.get(ctx);
-
-            return None;
-        };
-
-        *rx_fn.call(ctx, (), |ctx, _| {
-            eval(cell.1.get(ctx).as_ref()?, &|i| self.eval_cell(ctx, i))
-        })
+        // A cycle makes the cell evaluate to an error.
+        *cell
+            .2
+            .call(ctx, (), |ctx, _| {
+                eval(cell.1.get(ctx).as_ref()?, &|i| self.eval_cell(ctx, i))
+            })
+            .ok()?
     }
 }
 
@@ -50,9 +45,7 @@ impl Default for Spreasheet {
     fn default() -> Self {
         Self {
             dependent: Dependent::toplevel(),
-            cells: std::array::from_fn(|_| {
-                (Rc::from(""), Rx::new(None), RefCell::new(RxFn::new()))
-            }),
+            cells: std::array::from_fn(|_| (Rc::from(""), Rx::new(None), RxFnCell::new())),
         }
     }
 }
@@ -62,7 +62,7 @@ struct Scanner<'a> {
 }
 
 impl<'a> Scanner<'a> {
-    fn new(buf: &str) -> Scanner {
+    fn new(buf: &str) -> Scanner<'_> {
         Scanner {
             current_position: 0,
             it: buf.chars().peekable(),
@@ -104,9 +104,8 @@ impl<'a> Scanner<'a> {
         F: Fn(char) -> bool,
     {
         let mut it = self.it.clone();
-        match it.next() {
-            None => return false,
-            _ => (),
+        if it.next().is_none() {
+            return false;
         }
 
         if let Some(&ch) = it.peek() {
@@ -143,7 +142,7 @@ struct Lexer<'a> {
 }
 
 impl<'a> Lexer<'a> {
-    fn new(buf: &str) -> Lexer {
+    fn new(buf: &str) -> Lexer<'_> {
         Lexer {
             it: Scanner::new(buf),
         }
@@ -297,11 +296,11 @@ fn parse_unary_op(it: &mut Parser) -> Result<UnaryOperator, ()> {
 
 fn parse_binary_op(it: &mut Parser) -> Result<BinaryOperator, ()> {
     let tc = it.advance();
-    let operator = match &tc {
-        &Token::Plus => BinaryOperator::Plus,
-        &Token::Minus => BinaryOperator::Minus,
-        &Token::Star => BinaryOperator::Star,
-        &Token::Slash => BinaryOperator::Slash,
+    let operator = match tc {
+        Token::Plus => BinaryOperator::Plus,
+        Token::Minus => BinaryOperator::Minus,
+        Token::Star => BinaryOperator::Star,
+        Token::Slash => BinaryOperator::Slash,
         _ => {
             it.error();
             return Err(());
@@ -313,9 +312,9 @@ fn parse_binary_op(it: &mut Parser) -> Result<BinaryOperator, ()> {
 
 fn parse_primary(it: &mut Parser) -> Result<Expr, ()> {
     let tc = it.advance();
-    match &tc {
-        &Token::Number(n) => Ok(Expr::Number(*n)),
-        &Token::Identifier(ref s) => Ok(Expr::Variable(s.clone())),
+    match tc {
+        Token::Number(n) => Ok(Expr::Number(*n)),
+        Token::Identifier(s) => Ok(Expr::Variable(s.clone())),
         _ => {
             it.error();
             Err(())
@@ -369,7 +368,7 @@ impl<'a> Parser<'a> {
     fn advance(&mut self) -> &'a Token {
         let token = self.tokens.get(self.cursor);
         if let Some(token) = token {
-            self.cursor = self.cursor + 1;
+            self.cursor += 1;
             token
         } else {
             &Token::Eof
@@ -385,16 +384,6 @@ impl<'a> Parser<'a> {
             Err(())
         }
     }
-
-    fn optionally(&mut self, expected: TokenKind) -> Result<bool, ()> {
-        let token = self.peek();
-        if TokenKind::from(token) == expected {
-            self.expect(expected)?;
-            Ok(true)
-        } else {
-            Ok(false)
-        }
-    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -431,7 +420,7 @@ enum Precedence {
     Primary,
 }
 
-impl<'a> From<TokenKind> for Precedence {
+impl From<TokenKind> for Precedence {
     fn from(token: TokenKind) -> Precedence {
         match token {
             TokenKind::Plus | TokenKind::Minus => Precedence::Term,
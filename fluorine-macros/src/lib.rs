@@ -0,0 +1,93 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, parse_quote, Data, DeriveInput, Fields};
+
+/// Generates an `Rx{Name}` version of a struct where every field is wrapped in its own `Rx`, so
+/// that mutating one field only invalidates the dependents that read that field.
+///
+/// For every field `foo` the generated struct gets a tracked getter `foo(ctx)` and a `foo_mut()`
+/// that invalidates its readers. It can be created from the plain struct using `new` or `From`.
+#[proc_macro_derive(Reactive)]
+pub fn derive_reactive(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match reactive(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn reactive(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "Reactive can only be derived for structs",
+        ));
+    };
+
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "Reactive can only be derived for structs with named fields",
+        ));
+    };
+
+    let vis = &input.vis;
+    let name = &input.ident;
+    let rx_name = format_ident!("Rx{}", name);
+
+    let fields: Vec<_> = fields.named.iter().collect();
+    let field_vis: Vec<_> = fields.iter().map(|f| &f.vis).collect();
+    let field_names: Vec<_> = fields.iter().map(|f| f.ident.as_ref().unwrap()).collect();
+    let field_tys: Vec<_> = fields.iter().map(|f| &f.ty).collect();
+    let field_names_mut: Vec<_> = field_names
+        .iter()
+        .map(|name| format_ident!("{}_mut", name))
+        .collect();
+
+    let generics = &input.generics;
+    let (_, ty_generics, where_clause) = generics.split_for_impl();
+
+    // Rx needs its values to be Clone.
+    let mut impl_generics = generics.clone();
+    let impl_where_clause = impl_generics.make_where_clause();
+    for ty in &field_tys {
+        impl_where_clause
+            .predicates
+            .push(parse_quote!(#ty: ::core::clone::Clone));
+    }
+    let (impl_generics, _, impl_where_clause) = impl_generics.split_for_impl();
+
+    Ok(quote! {
+        #vis struct #rx_name #generics #where_clause {
+            #(#field_vis #field_names: ::fluorine::Rx<#field_tys>,)*
+        }
+
+        impl #impl_generics #rx_name #ty_generics #impl_where_clause {
+            #vis fn new(value: #name #ty_generics) -> Self {
+                #rx_name {
+                    #(#field_names: ::fluorine::Rx::new(value.#field_names),)*
+                }
+            }
+
+            #(
+                #field_vis fn #field_names(&self, ctx: &::fluorine::RxCtx) -> &#field_tys {
+                    self.#field_names.get(ctx)
+                }
+
+                #field_vis fn #field_names_mut(&mut self) -> &mut #field_tys {
+                    self.#field_names.get_mut()
+                }
+            )*
+        }
+
+        impl #impl_generics ::core::convert::From<#name #ty_generics>
+            for #rx_name #ty_generics #impl_where_clause
+        {
+            fn from(value: #name #ty_generics) -> Self {
+                #rx_name::new(value)
+            }
+        }
+    })
+}
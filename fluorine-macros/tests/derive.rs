@@ -0,0 +1,57 @@
+use std::cell::Cell;
+
+use fluorine::{Dependent, Reactive, RxFn};
+
+#[derive(Reactive)]
+struct Player {
+    hp: u32,
+    name: String,
+}
+
+#[test]
+fn test_fields_are_tracked_separately() {
+    let times_called = Cell::new(0);
+
+    let mut player = RxPlayer::new(Player {
+        hp: 10,
+        name: "Ferris".to_string(),
+    });
+
+    let mut f = RxFn::new();
+    let mut hp = |ctx, player: &RxPlayer| -> u32 {
+        *f.call(ctx, (), |ctx, ()| {
+            times_called.set(times_called.get() + 1);
+            *player.hp(ctx)
+        })
+    };
+
+    let dependent = Dependent::toplevel();
+    let ctx = &dependent.ctx();
+
+    assert_eq!(hp(ctx, &player), 10);
+
+    player.name_mut().push_str(" the crab");
+
+    assert_eq!(hp(ctx, &player), 10);
+    assert_eq!(times_called.get(), 1);
+    assert_eq!(player.name(ctx), "Ferris the crab");
+
+    *player.hp_mut() = 7;
+
+    assert_eq!(hp(ctx, &player), 7);
+    assert_eq!(times_called.get(), 2);
+}
+
+#[derive(Reactive)]
+struct Wrapper<T> {
+    value: T,
+}
+
+#[test]
+fn test_generics() {
+    let wrapper = RxWrapper::from(Wrapper { value: 3 });
+
+    let dependent = Dependent::toplevel();
+
+    assert_eq!(*wrapper.value(&dependent.ctx()), 3);
+}
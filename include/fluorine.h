@@ -0,0 +1,52 @@
+/* C interface of fluorine. Build the crate with the `capi` feature to get these symbols. */
+
+#ifndef FLUORINE_H
+#define FLUORINE_H
+
+#include <stdbool.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct FluorineToplevel FluorineToplevel;
+typedef struct FluorineF64 FluorineF64;
+typedef struct FluorineString FluorineString;
+typedef struct FluorineMemoF64 FluorineMemoF64;
+
+/* Only valid during the callback it was passed to. */
+typedef struct FluorineCtx FluorineCtx;
+
+typedef void (*FluorineDirtyCallback)(void *user_data);
+typedef void (*FluorineFrameCallback)(void *user_data, const FluorineCtx *ctx);
+typedef double (*FluorineComputeF64)(void *user_data, const FluorineCtx *ctx);
+
+FluorineToplevel *fluorine_toplevel_new(void);
+void fluorine_toplevel_free(FluorineToplevel *toplevel);
+void fluorine_toplevel_on_dirty(const FluorineToplevel *toplevel, FluorineDirtyCallback callback,
+                                void *user_data);
+bool fluorine_toplevel_take_dirty(const FluorineToplevel *toplevel);
+void fluorine_toplevel_frame(const FluorineToplevel *toplevel, FluorineFrameCallback callback,
+                             void *user_data);
+
+FluorineF64 *fluorine_f64_new(double value);
+void fluorine_f64_free(FluorineF64 *rx);
+double fluorine_f64_get(const FluorineF64 *rx, const FluorineCtx *ctx);
+void fluorine_f64_set(FluorineF64 *rx, double value);
+
+FluorineString *fluorine_string_new(const char *value);
+void fluorine_string_free(FluorineString *rx);
+/* Owned by the signal, valid until it gets set or freed. */
+const char *fluorine_string_get(const FluorineString *rx, const FluorineCtx *ctx);
+void fluorine_string_set(FluorineString *rx, const char *value);
+
+FluorineMemoF64 *fluorine_memo_f64_new(void);
+void fluorine_memo_f64_free(FluorineMemoF64 *memo);
+double fluorine_memo_f64_call(FluorineMemoF64 *memo, const FluorineCtx *ctx,
+                              FluorineComputeF64 compute, void *user_data);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
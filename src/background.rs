@@ -0,0 +1,270 @@
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, Sender},
+        Arc,
+    },
+};
+
+use crate::{Dependents, RxCtx, RxFn};
+
+/// What a [`BackgroundRxFn`] currently has to show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackgroundState<'a, O> {
+    /// The computation for the latest input hasn't finished yet.
+    Pending,
+    Ready(&'a O),
+}
+
+/// Tells long computations that their result isn't needed anymore, so they can stop early. See
+/// [`RxCtx::cancellation_token`] and [`BackgroundRxFn`], which cancels the computation for the
+/// previous input when the input changes.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+type Compute<I, O> = Arc<dyn Fn(I, &CancelToken) -> O + Send + Sync>;
+type Job = Box<dyn FnOnce() + Send>;
+
+/// A computation that is too expensive for the thread that owns the graph, like a big layout or
+/// a search.
+///
+/// The input gets collected on the graph's thread by the closure passed to
+/// [`BackgroundRxFn::call`], which is tracked like the closure of an [`RxFn`]. Whenever
+/// something it read changes the input gets handed to the `compute` function on another thread
+/// (through the `spawn` function passed to [`BackgroundRxFn::new`]) and the previous computation
+/// gets cancelled (see [`RxCtx::cancellation_token`]). The results come back through
+/// [`BackgroundRxFn::poll`], which has to be called regularly (for example once per frame) like
+/// [`crate::RxReceiver::poll`].
+pub struct BackgroundRxFn<I, O> {
+    starter: RxFn<(), ()>,
+    result: Option<O>,
+    pending: bool,
+    // Used to throw away the results of computations that got superseded while they were
+    // running.
+    current: u64,
+    dependents: Dependents,
+    sender: Sender<(u64, O)>,
+    receiver: Receiver<(u64, O)>,
+    compute: Compute<I, O>,
+    spawn: Box<dyn Fn(Job)>,
+}
+
+impl<I, O: fmt::Debug> fmt::Debug for BackgroundRxFn<I, O> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BackgroundRxFn")
+            .field("result", &self.result)
+            .field("pending", &self.pending)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<I: Send + 'static, O: Send + 'static> BackgroundRxFn<I, O> {
+    /// `spawn` has to run the jobs it gets on another thread, like [`std::thread::spawn`] or a
+    /// thread pool.
+    pub fn new(
+        spawn: impl Fn(Box<dyn FnOnce() + Send>) + 'static,
+        compute: impl Fn(I, &CancelToken) -> O + Send + Sync + 'static,
+    ) -> Self {
+        let (sender, receiver) = mpsc::channel();
+
+        BackgroundRxFn {
+            starter: RxFn::new(),
+            result: None,
+            pending: false,
+            current: 0,
+            dependents: Dependents::new(),
+            sender,
+            receiver,
+            compute: Arc::new(compute),
+            spawn: Box::new(spawn),
+        }
+    }
+
+    /// Starts a computation if that hasn't happened yet or if something `input` depends on has
+    /// changed. The previous result isn't shown while the new one is pending.
+    pub fn call(
+        &mut self,
+        ctx: &RxCtx,
+        mut input: impl FnMut(&RxCtx) -> I,
+    ) -> BackgroundState<'_, O> {
+        let BackgroundRxFn {
+            starter,
+            pending,
+            current,
+            dependents,
+            sender,
+            compute,
+            spawn,
+            ..
+        } = self;
+
+        starter.call(ctx, (), |ctx, ()| {
+            let input = input(ctx);
+
+            *current += 1;
+            *pending = true;
+            // This happens while the starter runs, but only affects readers of the state.
+            dependents.mark_dirty_unchecked();
+
+            let id = *current;
+            // This gets cancelled as soon as the input is invalidated, even if nothing calls this
+            // again to start the next computation.
+            let token = ctx.cancellation_token();
+            let sender = sender.clone();
+            let compute = compute.clone();
+
+            spawn(Box::new(move || {
+                let output = compute(input, &token);
+
+                if !token.is_cancelled() {
+                    // The receiving end might be gone already.
+                    let _ = sender.send((id, output));
+                }
+            }));
+        });
+
+        self.dependents.track(ctx);
+
+        match &self.result {
+            Some(result) if !self.pending => BackgroundState::Ready(result),
+            _ => BackgroundState::Pending,
+        }
+    }
+
+    /// Takes the results that have arrived. The readers only get invalidated if the result of
+    /// the latest computation was among them. Returns whether that was the case.
+    pub fn poll(&mut self) -> bool {
+        let mut ready = false;
+
+        for (id, output) in self.receiver.try_iter() {
+            if id == self.current {
+                self.result = Some(output);
+                self.pending = false;
+                ready = true;
+            }
+        }
+
+        if ready {
+            self.dependents.mark_dirty();
+        }
+
+        ready
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc, sync::Mutex, thread};
+
+    use super::*;
+    use crate::{Dependent, Rx};
+
+    #[test]
+    fn test_background_rx_fn() {
+        let jobs: Rc<RefCell<Vec<Job>>> = Rc::default();
+
+        let run = || {
+            for job in jobs.take() {
+                thread::spawn(job).join().unwrap();
+            }
+        };
+
+        let mut query = Rx::new(String::from("a"));
+        let tokens: Arc<Mutex<Vec<CancelToken>>> = Arc::default();
+
+        let mut search = BackgroundRxFn::new(
+            {
+                let jobs = jobs.clone();
+                move |job| jobs.borrow_mut().push(job)
+            },
+            {
+                let tokens = tokens.clone();
+                move |query: String, token: &CancelToken| {
+                    tokens.lock().unwrap().push(token.clone());
+                    query.repeat(3)
+                }
+            },
+        );
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(
+            search.call(ctx, |ctx| query.get(ctx).clone()),
+            BackgroundState::Pending
+        );
+        assert!(!search.poll());
+
+        run();
+        assert!(search.poll());
+        assert_eq!(
+            search.call(ctx, |ctx| query.get(ctx).clone()),
+            BackgroundState::Ready(&String::from("aaa"))
+        );
+
+        // the first job gets superseded before it finishes
+        query.set(String::from("b"));
+        search.call(ctx, |ctx| query.get(ctx).clone());
+        query.set(String::from("c"));
+        search.call(ctx, |ctx| query.get(ctx).clone());
+
+        run();
+
+        let tokens = tokens.lock().unwrap();
+        assert_eq!(tokens.len(), 3);
+        assert!(tokens[1].is_cancelled());
+        assert!(!tokens[2].is_cancelled());
+        assert!(search.poll());
+        assert_eq!(
+            search.call(ctx, |ctx| query.get(ctx).clone()),
+            BackgroundState::Ready(&String::from("ccc"))
+        );
+    }
+
+    #[test]
+    fn test_cancel_on_invalidation() {
+        let tokens: Arc<Mutex<Vec<CancelToken>>> = Arc::default();
+        let jobs: Rc<RefCell<Vec<Job>>> = Rc::default();
+
+        let mut query = Rx::new(1);
+
+        let mut search = BackgroundRxFn::new(
+            {
+                let jobs = jobs.clone();
+                move |job| jobs.borrow_mut().push(job)
+            },
+            {
+                let tokens = tokens.clone();
+                move |query: i32, token: &CancelToken| {
+                    tokens.lock().unwrap().push(token.clone());
+                    query
+                }
+            },
+        );
+
+        let dependent = Dependent::toplevel();
+        search.call(&dependent.ctx(), |ctx| *query.get(ctx));
+
+        for job in jobs.take() {
+            thread::spawn(job).join().unwrap();
+        }
+
+        let token = tokens.lock().unwrap()[0].clone();
+        assert!(!token.is_cancelled());
+
+        // nothing calls the search again, but the running computation already is outdated
+        query.set(2);
+        assert!(token.is_cancelled());
+    }
+}
@@ -0,0 +1,280 @@
+//! A C interface for using fluorine as the state layer of an application written in another
+//! language. Only available with the `capi` feature. The declarations are in
+//! `include/fluorine.h`.
+//!
+//! Everything is behind opaque handles that are created with a `_new` function and destroyed with
+//! the matching `_free` function. Like the rest of the crate none of this is thread-safe, so all
+//! handles have to stay on the thread that created them.
+//!
+//! Reading tracks the read in a context, which is only handed out to callbacks (by
+//! [`fluorine_toplevel_frame`] and [`fluorine_memo_f64_call`]) and is only valid until they return.
+
+use std::{
+    ffi::{c_char, c_void, CStr, CString},
+    rc::Rc,
+};
+
+use crate::{Dependent, Rx, RxCtx, RxFn};
+
+/// See [`Dependent::toplevel`].
+pub struct FluorineToplevel(Rc<Dependent>);
+
+pub struct FluorineF64(Rx<f64>);
+
+pub struct FluorineString(Rx<CString>);
+
+/// A memoized computation that produces an `f64`. See [`RxFn`].
+pub struct FluorineMemoF64(RxFn<(), f64>);
+
+/// Only valid during the callback it was passed to.
+pub type FluorineCtx = RxCtx<'static>;
+
+pub type FluorineDirtyCallback = extern "C" fn(user_data: *mut c_void);
+pub type FluorineFrameCallback = extern "C" fn(user_data: *mut c_void, ctx: *const FluorineCtx);
+pub type FluorineComputeF64 = extern "C" fn(user_data: *mut c_void, ctx: *const FluorineCtx) -> f64;
+
+fn ctx_ptr(ctx: &RxCtx) -> *const FluorineCtx {
+    (ctx as *const RxCtx).cast()
+}
+
+#[no_mangle]
+pub extern "C" fn fluorine_toplevel_new() -> *mut FluorineToplevel {
+    Box::into_raw(Box::new(FluorineToplevel(Dependent::toplevel())))
+}
+
+/// # Safety
+///
+/// `toplevel` has to be null or come from [`fluorine_toplevel_new`] and not have been freed.
+#[no_mangle]
+pub unsafe extern "C" fn fluorine_toplevel_free(toplevel: *mut FluorineToplevel) {
+    if !toplevel.is_null() {
+        drop(Box::from_raw(toplevel));
+    }
+}
+
+/// Calls `callback` with `user_data` every time something the toplevel read changes. See
+/// [`Dependent::on_dirty`]; the callback must not access any reactive state.
+///
+/// # Safety
+///
+/// `toplevel` has to be a valid handle, and `user_data` has to stay valid for as long as the
+/// callback might get called.
+#[no_mangle]
+pub unsafe extern "C" fn fluorine_toplevel_on_dirty(
+    toplevel: *const FluorineToplevel,
+    callback: FluorineDirtyCallback,
+    user_data: *mut c_void,
+) {
+    (*toplevel).0.on_dirty(move || callback(user_data));
+}
+
+/// See [`Dependent::take_dirty`].
+///
+/// # Safety
+///
+/// `toplevel` has to be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn fluorine_toplevel_take_dirty(toplevel: *const FluorineToplevel) -> bool {
+    (*toplevel).0.take_dirty()
+}
+
+/// Runs `callback` as a frame of the toplevel. See [`Dependent::frame`].
+///
+/// # Safety
+///
+/// `toplevel` has to be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn fluorine_toplevel_frame(
+    toplevel: *const FluorineToplevel,
+    callback: FluorineFrameCallback,
+    user_data: *mut c_void,
+) {
+    (*toplevel).0.frame(|ctx| callback(user_data, ctx_ptr(ctx)));
+}
+
+#[no_mangle]
+pub extern "C" fn fluorine_f64_new(value: f64) -> *mut FluorineF64 {
+    Box::into_raw(Box::new(FluorineF64(Rx::new(value))))
+}
+
+/// # Safety
+///
+/// `rx` has to be null or come from [`fluorine_f64_new`] and not have been freed.
+#[no_mangle]
+pub unsafe extern "C" fn fluorine_f64_free(rx: *mut FluorineF64) {
+    if !rx.is_null() {
+        drop(Box::from_raw(rx));
+    }
+}
+
+/// # Safety
+///
+/// `rx` has to be a valid handle and `ctx` a context that was passed to a callback that is still
+/// running.
+#[no_mangle]
+pub unsafe extern "C" fn fluorine_f64_get(rx: *const FluorineF64, ctx: *const FluorineCtx) -> f64 {
+    *(*rx).0.get(&*ctx)
+}
+
+/// Only invalidates the readers if the value is different.
+///
+/// # Safety
+///
+/// `rx` has to be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn fluorine_f64_set(rx: *mut FluorineF64, value: f64) {
+    (*rx).0.set_if_changed(value);
+}
+
+/// # Safety
+///
+/// `value` has to be a valid nul-terminated string. It gets copied.
+#[no_mangle]
+pub unsafe extern "C" fn fluorine_string_new(value: *const c_char) -> *mut FluorineString {
+    Box::into_raw(Box::new(FluorineString(Rx::new(
+        CStr::from_ptr(value).to_owned(),
+    ))))
+}
+
+/// # Safety
+///
+/// `rx` has to be null or come from [`fluorine_string_new`] and not have been freed.
+#[no_mangle]
+pub unsafe extern "C" fn fluorine_string_free(rx: *mut FluorineString) {
+    if !rx.is_null() {
+        drop(Box::from_raw(rx));
+    }
+}
+
+/// The returned string is owned by the signal and stays valid until it gets set or freed.
+///
+/// # Safety
+///
+/// `rx` has to be a valid handle and `ctx` a context that was passed to a callback that is still
+/// running.
+#[no_mangle]
+pub unsafe extern "C" fn fluorine_string_get(
+    rx: *const FluorineString,
+    ctx: *const FluorineCtx,
+) -> *const c_char {
+    (*rx).0.get(&*ctx).as_ptr()
+}
+
+/// Only invalidates the readers if the value is different.
+///
+/// # Safety
+///
+/// `rx` has to be a valid handle and `value` a valid nul-terminated string. It gets copied.
+#[no_mangle]
+pub unsafe extern "C" fn fluorine_string_set(rx: *mut FluorineString, value: *const c_char) {
+    (*rx).0.set_if_changed(CStr::from_ptr(value).to_owned());
+}
+
+#[no_mangle]
+pub extern "C" fn fluorine_memo_f64_new() -> *mut FluorineMemoF64 {
+    Box::into_raw(Box::new(FluorineMemoF64(RxFn::new())))
+}
+
+/// # Safety
+///
+/// `memo` has to be null or come from [`fluorine_memo_f64_new`] and not have been freed.
+#[no_mangle]
+pub unsafe extern "C" fn fluorine_memo_f64_free(memo: *mut FluorineMemoF64) {
+    if !memo.is_null() {
+        drop(Box::from_raw(memo));
+    }
+}
+
+/// Only calls `compute` if something it read through the context it gets has changed since the
+/// last time. See [`RxFn::call`].
+///
+/// # Safety
+///
+/// `memo` has to be a valid handle and `ctx` a context that was passed to a callback that is
+/// still running.
+#[no_mangle]
+pub unsafe extern "C" fn fluorine_memo_f64_call(
+    memo: *mut FluorineMemoF64,
+    ctx: *const FluorineCtx,
+    compute: FluorineComputeF64,
+    user_data: *mut c_void,
+) -> f64 {
+    *(*memo)
+        .0
+        .call(&*ctx, (), |ctx, ()| compute(user_data, ctx_ptr(ctx)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ptr;
+
+    use super::*;
+
+    struct State {
+        a: *mut FluorineF64,
+        memo: *mut FluorineMemoF64,
+        computed: u32,
+        result: f64,
+    }
+
+    extern "C" fn double(user_data: *mut c_void, ctx: *const FluorineCtx) -> f64 {
+        let state = unsafe { &mut *user_data.cast::<State>() };
+        state.computed += 1;
+
+        unsafe { fluorine_f64_get(state.a, ctx) * 2. }
+    }
+
+    extern "C" fn frame(user_data: *mut c_void, ctx: *const FluorineCtx) {
+        let state = user_data.cast::<State>();
+
+        unsafe {
+            (*state).result = fluorine_memo_f64_call((*state).memo, ctx, double, state.cast());
+        }
+    }
+
+    extern "C" fn count(user_data: *mut c_void) {
+        unsafe { *user_data.cast::<u32>() += 1 };
+    }
+
+    #[test]
+    fn test_capi() {
+        let mut dirty_calls = 0u32;
+
+        let mut state = State {
+            a: fluorine_f64_new(1.5),
+            memo: fluorine_memo_f64_new(),
+            computed: 0,
+            result: 0.,
+        };
+        let state_ptr: *mut c_void = ptr::addr_of_mut!(state).cast();
+
+        unsafe {
+            let toplevel = fluorine_toplevel_new();
+            fluorine_toplevel_on_dirty(toplevel, count, ptr::addr_of_mut!(dirty_calls).cast());
+
+            fluorine_toplevel_frame(toplevel, frame, state_ptr);
+            assert_eq!((*state_ptr.cast::<State>()).result, 3.);
+            assert!(!fluorine_toplevel_take_dirty(toplevel));
+
+            fluorine_f64_set((*state_ptr.cast::<State>()).a, 1.5);
+            assert!(!fluorine_toplevel_take_dirty(toplevel));
+
+            fluorine_f64_set((*state_ptr.cast::<State>()).a, 2.);
+            assert!(fluorine_toplevel_take_dirty(toplevel));
+
+            fluorine_toplevel_frame(toplevel, frame, state_ptr);
+            assert_eq!((*state_ptr.cast::<State>()).result, 4.);
+            assert_eq!((*state_ptr.cast::<State>()).computed, 2);
+
+            let name = fluorine_string_new(c"a".as_ptr());
+            fluorine_string_set(name, c"b".as_ptr());
+            fluorine_string_free(name);
+
+            fluorine_memo_f64_free((*state_ptr.cast::<State>()).memo);
+            fluorine_f64_free((*state_ptr.cast::<State>()).a);
+            fluorine_toplevel_free(toplevel);
+        }
+
+        assert!(dirty_calls > 0);
+    }
+}
@@ -0,0 +1,78 @@
+use std::{
+    cell::{Cell, RefCell},
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+/// Returned by [`crate::Rx::changed`] and [`crate::Dependents::changed`]. Resolves the next time
+/// the state gets marked as dirty after this was created.
+#[derive(Debug)]
+pub struct Changed(Rc<Waiter>);
+
+#[derive(Debug, Default)]
+pub(crate) struct Waiter {
+    changed: Cell<bool>,
+    waker: RefCell<Option<Waker>>,
+}
+
+impl Changed {
+    pub(crate) fn new() -> Self {
+        Changed(Rc::default())
+    }
+
+    pub(crate) fn waiter(&self) -> &Rc<Waiter> {
+        &self.0
+    }
+}
+
+impl Waiter {
+    pub(crate) fn wake(&self) {
+        self.changed.set(true);
+
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl Future for Changed {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0.changed.get() {
+            return Poll::Ready(());
+        }
+
+        *self.0.waker.borrow_mut() = Some(cx.waker().clone());
+
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rx;
+
+    #[test]
+    fn test_changed() {
+        let mut a = Rx::new(1);
+
+        let mut changed = a.changed();
+        let mut poll = || Pin::new(&mut changed).poll(&mut Context::from_waker(Waker::noop()));
+
+        assert_eq!(poll(), Poll::Pending);
+
+        a.set(2);
+
+        assert_eq!(poll(), Poll::Ready(()));
+
+        // dropped futures don't pile up
+        drop(a.changed());
+        drop(a.changed());
+
+        assert_eq!(a.dependents.0.waiters.borrow().len(), 1);
+    }
+}
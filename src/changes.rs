@@ -0,0 +1,186 @@
+//! A [`Stream`] of the values an [`crate::Rx`] gets set to. Only available with the `futures`
+//! feature.
+
+use std::{
+    cell::{Cell, RefCell},
+    fmt,
+    pin::Pin,
+    rc::{Rc, Weak},
+    task::{Context, Poll, Waker},
+};
+
+use futures_core::Stream;
+
+/// Returned by [`crate::Rx::changes`].
+#[derive(Debug)]
+pub struct Changes<T>(Rc<RefCell<Channel<T>>>);
+
+#[derive(Debug)]
+struct Channel<T> {
+    // Only the latest value is kept, so a stream that doesn't get polled doesn't grow.
+    latest: Option<T>,
+    waker: Option<Waker>,
+    // The Rx was dropped, so nothing is going to arrive anymore.
+    closed: bool,
+}
+
+impl<T> Stream for Changes<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let mut channel = self.0.borrow_mut();
+
+        if let Some(value) = channel.latest.take() {
+            return Poll::Ready(Some(value));
+        }
+
+        if channel.closed {
+            return Poll::Ready(None);
+        }
+
+        channel.waker = Some(cx.waker().clone());
+
+        Poll::Pending
+    }
+}
+
+/// The streams that have been handed out by an [`crate::Rx`].
+pub(crate) struct Subscribers<T> {
+    channels: RefCell<Vec<Weak<RefCell<Channel<T>>>>>,
+    // Set when the value was handed out mutably. What it was changed to can only be published the
+    // next time the Rx gets accessed.
+    stale: Cell<bool>,
+    // Stored when subscribing, so that publishing (which also happens on drop) doesn't need a
+    // Clone bound.
+    clone: Cell<Option<CloneFn<T>>>,
+}
+
+type CloneFn<T> = fn(&T) -> T;
+
+impl<T> fmt::Debug for Subscribers<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Subscribers")
+            .field("channels", &self.channels.borrow().len())
+            .field("stale", &self.stale.get())
+            .finish()
+    }
+}
+
+impl<T> Subscribers<T> {
+    pub(crate) fn new() -> Self {
+        Subscribers {
+            channels: RefCell::new(Vec::new()),
+            stale: Cell::new(false),
+            clone: Cell::new(None),
+        }
+    }
+
+    pub(crate) fn subscribe(&self) -> Changes<T>
+    where
+        T: Clone,
+    {
+        let channel = Rc::new(RefCell::new(Channel {
+            latest: None,
+            waker: None,
+            closed: false,
+        }));
+
+        self.channels.borrow_mut().push(Rc::downgrade(&channel));
+        self.clone.set(Some(T::clone));
+
+        Changes(channel)
+    }
+
+    /// Remembers to publish the value the next time it gets accessed.
+    pub(crate) fn mark_stale(&self) {
+        if self.clone.get().is_some() {
+            self.stale.set(true);
+        }
+    }
+
+    /// Publishes the value if it was handed out mutably since it was last published.
+    pub(crate) fn flush(&self, value: &T) {
+        if self.stale.get() {
+            self.publish(value);
+        }
+    }
+
+    pub(crate) fn publish(&self, value: &T) {
+        self.stale.set(false);
+
+        let Some(clone) = self.clone.get() else {
+            return;
+        };
+
+        self.channels.borrow_mut().retain(|channel| {
+            let Some(channel) = channel.upgrade() else {
+                return false;
+            };
+
+            let mut channel = channel.borrow_mut();
+            channel.latest = Some(clone(value));
+
+            if let Some(waker) = channel.waker.take() {
+                waker.wake();
+            }
+
+            true
+        });
+    }
+}
+
+impl<T> Drop for Subscribers<T> {
+    fn drop(&mut self) {
+        for channel in self.channels.get_mut().drain(..) {
+            if let Some(channel) = channel.upgrade() {
+                let mut channel = channel.borrow_mut();
+                channel.closed = true;
+
+                if let Some(waker) = channel.waker.take() {
+                    waker.wake();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::task::Waker;
+
+    use super::*;
+    use crate::Rx;
+
+    #[test]
+    fn test_changes() {
+        let mut a = Rx::new(1);
+
+        let mut changes = a.changes();
+        let mut next = || Pin::new(&mut changes).poll_next(&mut Context::from_waker(Waker::noop()));
+
+        assert_eq!(next(), Poll::Pending);
+
+        a.set(2);
+        assert_eq!(next(), Poll::Ready(Some(2)));
+
+        // only the latest value is kept
+        a.set(3);
+        a.set(4);
+        a.set_if_changed(4);
+
+        assert_eq!(next(), Poll::Ready(Some(4)));
+        assert_eq!(next(), Poll::Pending);
+
+        // changes through get_mut show up once the value gets accessed again
+        *a.get_mut() += 1;
+        assert_eq!(next(), Poll::Pending);
+        assert_eq!(*a.get_untracked_intentional(), 5);
+        assert_eq!(next(), Poll::Ready(Some(5)));
+
+        *a.get_mut() += 1;
+        drop(a);
+
+        assert_eq!(next(), Poll::Ready(Some(6)));
+        assert_eq!(next(), Poll::Ready(None));
+    }
+}
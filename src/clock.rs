@@ -0,0 +1,87 @@
+use std::time::Duration;
+
+use crate::{RxCell, RxCtx};
+
+/// The current time as a signal. It only moves when the host calls [`RxClock::tick`] (for example
+/// once per frame), which makes everything that reads it deterministic and easy to test.
+///
+/// Times are durations since a point the host chooses (like the start of the program), because
+/// [`std::time::Instant`] isn't available everywhere (for example on `wasm32-unknown-unknown`,
+/// where the time would come from `performance.now()`).
+#[derive(Debug)]
+pub struct RxClock {
+    // The current time and how far the latest tick advanced it.
+    time: RxCell<(Duration, Duration)>,
+}
+
+impl RxClock {
+    pub fn new(start: Duration) -> Self {
+        RxClock {
+            time: RxCell::new((start, Duration::ZERO)),
+        }
+    }
+
+    pub fn now(&self, ctx: &RxCtx) -> Duration {
+        self.time.get(ctx).0
+    }
+
+    /// How far the latest [`RxClock::tick`] advanced the time, for example to step an animation.
+    pub fn delta(&self, ctx: &RxCtx) -> Duration {
+        self.time.get(ctx).1
+    }
+
+    /// See [`crate::Rx::get_untracked`].
+    #[track_caller]
+    pub fn now_untracked(&self) -> Duration {
+        self.time.get_untracked().0
+    }
+
+    /// Advances the time to `now` and invalidates everything that read it. Returns whether the
+    /// time changed. Going backwards is ignored.
+    #[track_caller]
+    pub fn tick(&self, now: Duration) -> bool {
+        let (previous, _) = self.time.get_untracked_intentional();
+
+        if now <= previous {
+            return false;
+        }
+
+        self.time.set((now, now - previous))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::{Dependent, RxFn};
+
+    #[test]
+    fn test_clock() {
+        let start = Duration::from_secs(10);
+        let clock = RxClock::new(start);
+        let times_called = Cell::new(0);
+
+        let mut f = RxFn::new();
+        let mut elapsed = |ctx| -> Duration {
+            *f.call(ctx, (), |ctx, ()| {
+                times_called.set(times_called.get() + 1);
+                clock.now(ctx) - start
+            })
+        };
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(elapsed(ctx), Duration::ZERO);
+
+        assert!(clock.tick(start + Duration::from_millis(16)));
+        assert_eq!(elapsed(ctx), Duration::from_millis(16));
+        assert_eq!(clock.delta(ctx), Duration::from_millis(16));
+
+        assert!(!clock.tick(start));
+        assert_eq!(elapsed(ctx), Duration::from_millis(16));
+        assert_eq!(times_called.get(), 2);
+    }
+}
@@ -0,0 +1,104 @@
+//! Small derived values that would otherwise each need their own [`crate::RxFn`].
+//!
+//! The results are cached in a [`Projection`] and compared to the previous ones, so readers only
+//! get invalidated if the result actually changes (see [`crate::RxFn::call_with_eq`]).
+
+use crate::{Projection, Rx, RxCtx};
+
+/// Combines two values with `f`.
+pub fn map2<'p, A, B, O>(
+    ctx: &RxCtx,
+    cache: &'p mut Projection<O>,
+    a: &Rx<A>,
+    b: &Rx<B>,
+    mut f: impl FnMut(&A, &B) -> O,
+) -> &'p O
+where
+    A: Clone,
+    B: Clone,
+    O: PartialEq,
+{
+    cache
+        .0
+        .call_with_eq(ctx, (), |ctx, ()| f(a.get(ctx), b.get(ctx)))
+}
+
+/// Both values as a pair.
+pub fn zip<'p, A, B>(
+    ctx: &RxCtx,
+    cache: &'p mut Projection<(A, B)>,
+    a: &Rx<A>,
+    b: &Rx<B>,
+) -> &'p (A, B)
+where
+    A: Clone + PartialEq,
+    B: Clone + PartialEq,
+{
+    map2(ctx, cache, a, b, |a, b| (a.clone(), b.clone()))
+}
+
+/// Whether all the values are true. Only reads until the first false one, so changes to the
+/// ones after it don't invalidate the result.
+pub fn all(ctx: &RxCtx, cache: &mut Projection<bool>, values: &[Rx<bool>]) -> bool {
+    *cache
+        .0
+        .call_with_eq(ctx, (), |ctx, ()| values.iter().all(|v| *v.get(ctx)))
+}
+
+/// Whether any of the values is true. Only reads until the first true one, so changes to the
+/// ones after it don't invalidate the result.
+pub fn any(ctx: &RxCtx, cache: &mut Projection<bool>, values: &[Rx<bool>]) -> bool {
+    *cache
+        .0
+        .call_with_eq(ctx, (), |ctx, ()| values.iter().any(|v| *v.get(ctx)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::{Dependent, RxFn};
+
+    #[test]
+    fn test_combinators() {
+        let mut width = Rx::new(2);
+        let height = Rx::new(3);
+        let mut flags = vec![Rx::new(true), Rx::new(false), Rx::new(true)];
+
+        let mut area = Projection::new();
+        let mut size = Projection::new();
+        let mut all_set = Projection::new();
+        let mut any_set = Projection::new();
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(*map2(ctx, &mut area, &width, &height, |w, h| w * h), 6);
+        assert_eq!(*zip(ctx, &mut size, &width, &height), (2, 3));
+        assert!(!all(ctx, &mut all_set, &flags));
+
+        width.set(4);
+        flags[1].set(true);
+
+        assert_eq!(*map2(ctx, &mut area, &width, &height, |w, h| w * h), 12);
+        assert_eq!(*zip(ctx, &mut size, &width, &height), (4, 3));
+        assert!(all(ctx, &mut all_set, &flags));
+
+        let times_called = Cell::new(0);
+        let mut f = RxFn::new();
+        let mut run = |ctx, flags: &[Rx<bool>]| -> bool {
+            *f.call(ctx, (), |ctx, ()| {
+                times_called.set(times_called.get() + 1);
+                any(ctx, &mut any_set, flags)
+            })
+        };
+
+        assert!(run(ctx, &flags));
+
+        // any stopped at the first flag, so it doesn't depend on the others
+        flags[2].set(false);
+        assert!(run(ctx, &flags));
+        assert_eq!(times_called.get(), 1);
+    }
+}
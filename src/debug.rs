@@ -0,0 +1,150 @@
+//! Tools for looking at the dependency graph while developing.
+
+use std::{collections::HashMap, fmt::Write, rc::Rc};
+
+use crate::Dependent;
+
+/// Renders everything that `roots` (transitively) depend on as a graph in the DOT format of
+/// Graphviz. Edges point in the direction changes propagate and dirty computations are drawn in
+/// red.
+pub fn to_dot(roots: &[Rc<Dependent>]) -> String {
+    let mut dot = Dot {
+        ids: HashMap::new(),
+        out: String::from("digraph {\n"),
+    };
+
+    let mut worklist: Vec<Rc<Dependent>> = Vec::new();
+
+    for root in roots {
+        if let (_, true) = dot.computation(root) {
+            worklist.push(root.clone());
+        }
+    }
+
+    while let Some(dependent) = worklist.pop() {
+        let (dependent_id, _) = dot.computation(&dependent);
+
+        for dependency in dependent.dependencies() {
+            let dependency_id = match dependency.computation() {
+                Some(computation) => {
+                    let (id, new) = dot.computation(&computation);
+
+                    if new {
+                        worklist.push(computation);
+                    }
+
+                    id
+                }
+                None => {
+                    dot.node(Rc::as_ptr(&dependency.0).cast(), || {
+                        format!(
+                            "shape=ellipse, label={}",
+                            quote(dependency.name().unwrap_or("signal")),
+                        )
+                    })
+                    .0
+                }
+            };
+
+            writeln!(dot.out, "    n{} -> n{};", dependency_id, dependent_id).unwrap();
+        }
+    }
+
+    dot.out.push_str("}\n");
+
+    dot.out
+}
+
+struct Dot {
+    ids: HashMap<*const (), usize>,
+    out: String,
+}
+
+impl Dot {
+    /// Returns the id of the node and whether it was newly added.
+    fn node(&mut self, ptr: *const (), attrs: impl FnOnce() -> String) -> (usize, bool) {
+        if let Some(&id) = self.ids.get(&ptr) {
+            return (id, false);
+        }
+
+        let id = self.ids.len();
+        self.ids.insert(ptr, id);
+
+        writeln!(self.out, "    n{} [{}];", id, attrs()).unwrap();
+
+        (id, true)
+    }
+
+    fn computation(&mut self, dependent: &Rc<Dependent>) -> (usize, bool) {
+        self.node(Rc::as_ptr(dependent).cast(), || {
+            let generation = dependent.generation.get();
+
+            let label = match dependent.name() {
+                Some(name) => format!("{}\ngeneration {}", name, generation),
+                None => format!("generation {}", generation),
+            };
+
+            format!(
+                "shape=box, label={}{}",
+                quote(&label),
+                if dependent.dirty() { ", color=red" } else { "" },
+            )
+        })
+    }
+}
+
+/// Turns `label` into a quoted DOT string. Unlike Rust's debug output this leaves everything but
+/// quotes, backslashes and line breaks alone, since DOT only knows those escapes.
+fn quote(label: &str) -> String {
+    let mut quoted = String::with_capacity(label.len() + 2);
+    quoted.push('"');
+
+    for c in label.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c => quoted.push(c),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Rx, RxFn};
+
+    #[test]
+    fn test_to_dot() {
+        let mut a = Rx::new_named("a", 1);
+
+        let mut f = RxFn::new_named("f");
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        f.call(ctx, (), |ctx, ()| *a.get(ctx));
+
+        *a.get_mut() = 2;
+
+        assert_eq!(
+            to_dot(std::slice::from_ref(&dependent)),
+            "digraph {
+    n0 [shape=box, label=\"generation 0\", color=red];
+    n1 [shape=box, label=\"f\\ngeneration 1\", color=red];
+    n1 -> n0;
+    n2 [shape=ellipse, label=\"a\"];
+    n2 -> n1;
+}
+",
+        );
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote("a \"b\" c:\\ ü\nd"), r#""a \"b\" c:\\ ü\nd""#);
+    }
+}
@@ -0,0 +1,235 @@
+use std::collections::VecDeque;
+
+use crate::{Dependents, RxCtx};
+
+/// Identifies an element of an [`RxDeque`] for as long as it's in there, no matter how many
+/// elements get pushed or popped in front of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DequeId(u64);
+
+/// A reactive double-ended queue where every element has its own list of dependents.
+///
+/// Reading an element by its [`DequeId`] only subscribes to that element. Anything that depends
+/// on positions (like reading by index or the length) also subscribes to the structure, which
+/// changes whenever elements are pushed or popped.
+#[derive(Debug)]
+pub struct RxDeque<T> {
+    // The ids always increase from front to back, so elements can be found with a binary search.
+    items: VecDeque<Item<T>>,
+    next_front: u64,
+    next_back: u64,
+    structure: Dependents,
+}
+
+#[derive(Debug)]
+struct Item<T> {
+    id: DequeId,
+    value: T,
+    dependents: Dependents,
+}
+
+impl<T> Default for RxDeque<T> {
+    fn default() -> Self {
+        RxDeque::new()
+    }
+}
+
+impl<T> RxDeque<T> {
+    pub fn new() -> Self {
+        RxDeque {
+            items: VecDeque::new(),
+            // Starting in the middle leaves room for the same number of pushes on either side.
+            next_front: u64::MAX / 2,
+            next_back: u64::MAX / 2 + 1,
+            structure: Dependents::new(),
+        }
+    }
+
+    fn position(&self, id: DequeId) -> Option<usize> {
+        self.items.binary_search_by_key(&id, |item| item.id).ok()
+    }
+
+    pub fn len(&self, ctx: &RxCtx) -> usize {
+        self.structure.track(ctx);
+
+        self.items.len()
+    }
+
+    pub fn is_empty(&self, ctx: &RxCtx) -> bool {
+        self.len(ctx) == 0
+    }
+
+    /// Only gets invalidated when elements are pushed or popped.
+    pub fn ids(&self, ctx: &RxCtx) -> impl Iterator<Item = DequeId> + '_ {
+        self.structure.track(ctx);
+
+        self.items.iter().map(|item| item.id)
+    }
+
+    pub fn get(&self, ctx: &RxCtx, index: usize) -> Option<&T> {
+        self.structure.track(ctx);
+
+        let item = self.items.get(index)?;
+        item.dependents.track(ctx);
+
+        Some(&item.value)
+    }
+
+    /// Only gets invalidated when this element changes or gets popped.
+    pub fn get_by_id(&self, ctx: &RxCtx, id: DequeId) -> Option<&T> {
+        let Some(index) = self.position(id) else {
+            // It's gone for good, because ids never get reused.
+            return None;
+        };
+
+        let item = &self.items[index];
+        item.dependents.track(ctx);
+
+        Some(&item.value)
+    }
+
+    pub fn front(&self, ctx: &RxCtx) -> Option<&T> {
+        self.get(ctx, 0)
+    }
+
+    pub fn back(&self, ctx: &RxCtx) -> Option<&T> {
+        self.structure.track(ctx);
+
+        let item = self.items.back()?;
+        item.dependents.track(ctx);
+
+        Some(&item.value)
+    }
+
+    /// Subscribes to the structure and to every element.
+    pub fn iter(&self, ctx: &RxCtx) -> impl Iterator<Item = (DequeId, &T)> {
+        self.structure.track(ctx);
+
+        for item in &self.items {
+            item.dependents.track(ctx);
+        }
+
+        self.items.iter().map(|item| (item.id, &item.value))
+    }
+
+    #[track_caller]
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        let item = self.items.get_mut(index)?;
+        item.dependents.mark_dirty();
+
+        Some(&mut item.value)
+    }
+
+    #[track_caller]
+    pub fn get_mut_by_id(&mut self, id: DequeId) -> Option<&mut T> {
+        self.get_mut(self.position(id)?)
+    }
+
+    #[track_caller]
+    pub fn push_front(&mut self, value: T) -> DequeId {
+        let id = DequeId(self.next_front);
+        self.next_front -= 1;
+
+        self.push(id, value, VecDeque::push_front)
+    }
+
+    #[track_caller]
+    pub fn push_back(&mut self, value: T) -> DequeId {
+        let id = DequeId(self.next_back);
+        self.next_back += 1;
+
+        self.push(id, value, VecDeque::push_back)
+    }
+
+    #[track_caller]
+    fn push(
+        &mut self,
+        id: DequeId,
+        value: T,
+        push: fn(&mut VecDeque<Item<T>>, Item<T>),
+    ) -> DequeId {
+        push(
+            &mut self.items,
+            Item {
+                id,
+                value,
+                dependents: Dependents::new(),
+            },
+        );
+
+        self.structure.mark_dirty();
+
+        id
+    }
+
+    #[track_caller]
+    pub fn pop_front(&mut self) -> Option<T> {
+        let item = self.items.pop_front()?;
+
+        Some(self.popped(item))
+    }
+
+    #[track_caller]
+    pub fn pop_back(&mut self) -> Option<T> {
+        let item = self.items.pop_back()?;
+
+        Some(self.popped(item))
+    }
+
+    #[track_caller]
+    fn popped(&mut self, item: Item<T>) -> T {
+        item.dependents.mark_dirty();
+        self.structure.mark_dirty();
+
+        item.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::{Dependent, RxFn};
+
+    #[test]
+    fn test_deque() {
+        let mut deque = RxDeque::new();
+
+        let a = deque.push_back("a");
+        let b = deque.push_front("b");
+
+        assert!(b < a);
+
+        let times_called = Cell::new(0);
+
+        let mut f = RxFn::new();
+        let mut get = |ctx, deque: &RxDeque<&'static str>| -> Option<&'static str> {
+            *f.call(ctx, (), |ctx, ()| {
+                times_called.set(times_called.get() + 1);
+                deque.get_by_id(ctx, a).copied()
+            })
+        };
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(get(ctx, &deque), Some("a"));
+
+        deque.push_front("c");
+        deque.pop_front();
+        *deque.get_mut_by_id(b).unwrap() = "d";
+
+        assert_eq!(get(ctx, &deque), Some("a"));
+        assert_eq!(times_called.get(), 1);
+        assert_eq!(
+            deque.iter(ctx).map(|(_, v)| *v).collect::<Vec<_>>(),
+            ["d", "a"]
+        );
+
+        deque.pop_back();
+
+        assert_eq!(get(ctx, &deque), None);
+        assert_eq!(times_called.get(), 2);
+    }
+}
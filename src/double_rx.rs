@@ -0,0 +1,93 @@
+use std::mem;
+
+use crate::{Rx, RxCtx};
+
+/// A value with two buffers, for decoupling the code that changes state (like input handlers)
+/// from the code that reads it.
+///
+/// Writers change the next value whenever they want without invalidating anything. The readers
+/// keep seeing the current value until [`DoubleRx::commit`] gets called (usually at the start of
+/// a frame), which makes the next value current and invalidates the readers once.
+#[derive(Debug)]
+pub struct DoubleRx<T> {
+    current: Rx<T>,
+    next: T,
+    changed: bool,
+}
+
+impl<T: Clone> DoubleRx<T> {
+    pub fn new(value: T) -> Self {
+        DoubleRx {
+            next: value.clone(),
+            current: Rx::new(value),
+            changed: false,
+        }
+    }
+
+    /// The committed value.
+    pub fn get(&self, ctx: &RxCtx) -> &T {
+        self.current.get(ctx)
+    }
+
+    /// The value that is going to be committed.
+    pub fn next(&self) -> &T {
+        &self.next
+    }
+
+    pub fn next_mut(&mut self) -> &mut T {
+        self.changed = true;
+
+        &mut self.next
+    }
+
+    pub fn set_next(&mut self, value: T) {
+        *self.next_mut() = value;
+    }
+
+    /// Makes the next value current if it was changed since the last commit. Returns whether
+    /// that was the case. The next value then starts out as a copy of the new current one.
+    #[track_caller]
+    pub fn commit(&mut self) -> bool {
+        if !mem::take(&mut self.changed) {
+            return false;
+        }
+
+        let current = self.current.get_mut();
+
+        mem::swap(current, &mut self.next);
+        self.next.clone_from(current);
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Dependent;
+
+    #[test]
+    fn test_double_rx() {
+        let mut clicks = DoubleRx::new(Vec::new());
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert!(clicks.get(ctx).is_empty());
+        dependent.take_dirty();
+
+        clicks.next_mut().push(1);
+        clicks.next_mut().push(2);
+
+        assert!(!dependent.dirty());
+        assert!(clicks.get(ctx).is_empty());
+
+        assert!(clicks.commit());
+        assert!(dependent.take_dirty());
+        assert_eq!(clicks.get(ctx), &[1, 2]);
+        assert_eq!(clicks.next(), &[1, 2]);
+
+        assert!(!clicks.commit());
+        assert!(!dependent.dirty());
+    }
+}
@@ -0,0 +1,213 @@
+//! Glue for using fluorine with [egui](::egui). Only available with the `egui` feature.
+
+use std::{borrow::Cow, ops::RangeInclusive};
+
+use ::egui::{
+    emath::Numeric, epaint::ClippedShape, Context, DragValue, Rect, Response, Sense, Slider, Ui,
+    Vec2,
+};
+
+use crate::{Dependent, Rx, RxCtx, RxFn};
+
+/// Requests a repaint of `egui_ctx` every time `dependent` gets invalidated, so the UI only has to
+/// be redrawn when something changed.
+pub fn repaint_on_dirty(dependent: &Dependent, egui_ctx: &Context) {
+    let egui_ctx = egui_ctx.clone();
+
+    dependent.on_dirty(move || egui_ctx.request_repaint());
+}
+
+/// A single line text field that edits `rx`. The value gets read without tracking it (the widget
+/// shows the current value every frame anyway) and only gets written if it was edited. Returns
+/// whether that was the case.
+#[track_caller]
+pub fn bind_text(ui: &mut Ui, rx: &mut Rx<String>) -> bool {
+    let mut text = Cow::Borrowed(rx.get_untracked_intentional().as_str());
+
+    ui.text_edit_singleline(&mut text);
+
+    match text {
+        Cow::Owned(text) => rx.set_if_changed(text),
+        Cow::Borrowed(_) => false,
+    }
+}
+
+/// A checkbox for `rx`. See [`bind_text`].
+#[track_caller]
+pub fn bind_checkbox(ui: &mut Ui, rx: &mut Rx<bool>, text: &str) -> bool {
+    let mut checked = *rx.get_untracked_intentional();
+
+    ui.checkbox(&mut checked, text);
+
+    rx.set_if_changed(checked)
+}
+
+/// A slider for `rx`. See [`bind_text`].
+#[track_caller]
+pub fn bind_slider<T: Numeric>(ui: &mut Ui, rx: &mut Rx<T>, range: RangeInclusive<T>) -> bool {
+    let mut value = *rx.get_untracked_intentional();
+
+    ui.add(Slider::new(&mut value, range));
+
+    rx.set_if_changed(value)
+}
+
+/// A [`DragValue`] for `rx`. See [`bind_text`].
+#[track_caller]
+pub fn bind_drag_value<T: Numeric>(ui: &mut Ui, rx: &mut Rx<T>) -> bool {
+    let mut value = *rx.get_untracked_intentional();
+
+    ui.add(DragValue::new(&mut value));
+
+    rx.set_if_changed(value)
+}
+
+/// The state [`memo_ui`] keeps between frames.
+#[derive(Debug, Default)]
+pub struct MemoUi(RxFn<Vec2, Painted>);
+
+#[derive(Debug)]
+struct Painted {
+    rect: Rect,
+    shapes: Vec<ClippedShape>,
+}
+
+impl MemoUi {
+    pub fn new() -> Self {
+        MemoUi::default()
+    }
+}
+
+/// Only runs `add_contents` if something it depends on or the available space has changed since
+/// the last frame. Otherwise the shapes it painted last time get painted again at the current
+/// position.
+///
+/// Widgets that get skipped don't react to input, so this is only useful for content that is
+/// expensive to lay out but isn't interactive, like large amounts of text or plots.
+pub fn memo_ui(
+    ctx: &RxCtx,
+    ui: &mut Ui,
+    memo: &mut MemoUi,
+    add_contents: impl FnOnce(&RxCtx, &mut Ui),
+) -> Response {
+    let mut add_contents = Some(add_contents);
+    let mut response = None;
+
+    let painted = memo.0.call(ctx, ui.available_size(), |ctx, _| {
+        let layer_id = ui.layer_id();
+        let shape_count = |ui: &Ui| {
+            ui.ctx()
+                .graphics(|graphics| graphics.get(layer_id).map_or(0, |l| l.all_entries().len()))
+        };
+
+        let start = shape_count(ui);
+
+        // The unwrap works because the closure only gets called once.
+        let inner = ui.scope(|ui| add_contents.take().unwrap()(ctx, ui));
+
+        let shapes = ui.ctx().graphics(|graphics| {
+            graphics.get(layer_id).map_or(Vec::new(), |list| {
+                list.all_entries().skip(start).cloned().collect()
+            })
+        });
+
+        let rect = inner.response.rect;
+        response = Some(inner.response);
+
+        Painted { rect, shapes }
+    });
+
+    if let Some(response) = response {
+        return response;
+    }
+
+    let (rect, response) = ui.allocate_exact_size(painted.rect.size(), Sense::hover());
+    let delta = rect.min - painted.rect.min;
+    let clip_rect = ui.clip_rect();
+
+    ui.ctx().graphics_mut(|graphics| {
+        let list = graphics.entry(ui.layer_id());
+
+        for ClippedShape { shape, .. } in &painted.shapes {
+            let mut shape = shape.clone();
+            shape.translate(delta);
+
+            list.add(clip_rect, shape);
+        }
+    });
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use ::egui::{CentralPanel, RawInput};
+
+    use super::*;
+    use crate::Rx;
+
+    #[test]
+    fn test_memo_ui() {
+        let egui_ctx = Context::default();
+
+        let mut text = Rx::new(String::from("hello"));
+        let mut memo = MemoUi::new();
+        let times_called = Cell::new(0);
+
+        let dependent = Dependent::toplevel();
+
+        let mut frame = |text: &Rx<String>| {
+            let ctx = &dependent.ctx();
+
+            egui_ctx
+                .run(RawInput::default(), |egui_ctx| {
+                    CentralPanel::default().show(egui_ctx, |ui| {
+                        memo_ui(ctx, ui, &mut memo, |ctx, ui| {
+                            times_called.set(times_called.get() + 1);
+                            ui.label(text.get(ctx));
+                        });
+                    });
+                })
+                .shapes
+        };
+
+        let first = frame(&text);
+        assert_eq!(times_called.get(), 1);
+
+        let second = frame(&text);
+        assert_eq!(times_called.get(), 1);
+        assert_eq!(first.len(), second.len());
+
+        text.set(String::from("hello world"));
+
+        frame(&text);
+        assert_eq!(times_called.get(), 2);
+    }
+
+    #[test]
+    fn test_bind() {
+        let egui_ctx = Context::default();
+
+        let mut text = Rx::new(String::from("hello"));
+        let mut enabled = Rx::new(true);
+        let mut size = Rx::new(2.5);
+        let mut count = Rx::new(3);
+
+        for _ in 0..2 {
+            let _ = egui_ctx.run(RawInput::default(), |egui_ctx| {
+                CentralPanel::default().show(egui_ctx, |ui| {
+                    // nothing changes without input
+                    assert!(!bind_text(ui, &mut text));
+                    assert!(!bind_checkbox(ui, &mut enabled, "enabled"));
+                    assert!(!bind_slider(ui, &mut size, 0.0..=10.0));
+                    assert!(!bind_drag_value(ui, &mut count));
+                });
+            });
+        }
+
+        assert!(text.dependents().is_empty());
+        assert_eq!(*text.get_untracked(), "hello");
+    }
+}
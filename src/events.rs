@@ -0,0 +1,197 @@
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+use crate::{Dependent, Dependents, RxCtx};
+
+/// A queue of events, for modeling things like input in the same graph as the state that is
+/// derived from it.
+///
+/// Unlike an [`crate::Rx`] this doesn't have a current value. Every computation that reads this
+/// gets the events that were emitted since it last read them, and events get dropped once
+/// everything that reads them has moved past them. A computation that reran without reading this
+/// keeps its place (and keeps the events after it around) until it reads again or gets dropped.
+/// The readers have to be computations like an [`crate::RxFn`] or run in
+/// [`Dependent::frame`], because a plain [`Dependent::toplevel`] never starts a new run.
+#[derive(Debug)]
+pub struct RxEvents<E> {
+    events: Vec<E>,
+    // How many events have already been dropped from the front, so that the readers can refer to
+    // events by their index since the start.
+    offset: usize,
+    readers: RefCell<Vec<Reader>>,
+    dependents: Dependents,
+}
+
+#[derive(Debug)]
+struct Reader {
+    dependent: Weak<Dependent>,
+    generation: u64,
+    start: usize,
+    end: usize,
+}
+
+impl<E> Default for RxEvents<E> {
+    fn default() -> Self {
+        RxEvents::new()
+    }
+}
+
+impl<E> RxEvents<E> {
+    pub fn new() -> Self {
+        RxEvents {
+            events: Vec::new(),
+            offset: 0,
+            readers: RefCell::new(Vec::new()),
+            dependents: Dependents::new(),
+        }
+    }
+
+    /// The events that were emitted since the previous run of the computation that is currently
+    /// being evaluated in `ctx`. A computation that reads this for the first time starts out
+    /// without any events.
+    pub fn read(&self, ctx: &RxCtx) -> &[E] {
+        self.read_since(ctx).unwrap_or(&[])
+    }
+
+    /// Like [`RxEvents::read`], but returns `None` instead of an empty slice if the computation
+    /// reads this for the first time.
+    pub(crate) fn read_since(&self, ctx: &RxCtx) -> Option<&[E]> {
+        self.dependents.track(ctx);
+
+        let generation = ctx.dependent.generation.get();
+        let len = self.offset + self.events.len();
+
+        let mut readers = self.readers.borrow_mut();
+
+        let reader = readers
+            .iter_mut()
+            .find(|reader| reader.dependent.as_ptr() == Rc::as_ptr(ctx.dependent));
+
+        let (start, end) = match reader {
+            Some(reader) => {
+                // reading again during the same run gives the same events
+                if reader.generation != generation {
+                    reader.generation = generation;
+                    reader.start = reader.end;
+                    reader.end = len;
+                }
+
+                (reader.start, reader.end)
+            }
+            None => {
+                readers.push(Reader {
+                    dependent: Rc::downgrade(ctx.dependent),
+                    generation,
+                    start: len,
+                    end: len,
+                });
+
+                return None;
+            }
+        };
+
+        Some(&self.events[start - self.offset..end - self.offset])
+    }
+
+    #[track_caller]
+    pub fn emit(&mut self, event: E) {
+        let readers = self.readers.get_mut();
+
+        readers.retain(|reader| reader.dependent.strong_count() > 0);
+
+        // Drop what every reader has moved past. The events a reader got during its latest run
+        // have to stay, because it might read them again before that run is over.
+        let seen = readers
+            .iter()
+            .map(|reader| reader.start)
+            .min()
+            .unwrap_or(self.offset + self.events.len());
+
+        self.events.drain(..seen - self.offset);
+        self.offset = seen;
+
+        self.events.push(event);
+        self.dependents.mark_dirty();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RxFn;
+
+    #[test]
+    fn test_events() {
+        let mut clicks = RxEvents::new();
+
+        let mut count = RxFn::new();
+        let mut total = 0;
+        let mut run = |ctx, clicks: &RxEvents<u32>| {
+            count.call(ctx, (), |ctx, ()| {
+                total += clicks.read(ctx).iter().sum::<u32>();
+                // a second read in the same run sees the same events
+                assert_eq!(clicks.read(ctx).len(), clicks.read(ctx).len());
+            });
+        };
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        clicks.emit(100);
+        run(ctx, &clicks);
+
+        clicks.emit(1);
+        clicks.emit(2);
+        run(ctx, &clicks);
+        run(ctx, &clicks);
+
+        clicks.emit(3);
+        run(ctx, &clicks);
+
+        assert_eq!(total, 6);
+
+        // what the latest run got is kept until the reader moves past it
+        clicks.emit(4);
+        assert_eq!(clicks.events, [3, 4]);
+    }
+
+    #[test]
+    fn test_emit_during_run() {
+        let clicks = RefCell::new(RxEvents::new());
+        let dependent = Dependent::toplevel();
+
+        dependent.frame(|ctx| clicks.borrow().read(ctx).len());
+        clicks.borrow_mut().emit(1);
+
+        dependent.frame(|ctx| {
+            assert_eq!(clicks.borrow().read(ctx), [1]);
+
+            // like an event handler that runs between two reads of the same frame
+            clicks.borrow_mut().emit(2);
+
+            assert_eq!(clicks.borrow().read(ctx), [1]);
+        });
+
+        assert_eq!(
+            dependent.frame(|ctx| clicks.borrow().read(ctx).to_vec()),
+            [2]
+        );
+    }
+
+    #[test]
+    fn test_reader_that_skipped_a_run() {
+        let mut clicks = RxEvents::new();
+        let dependent = Dependent::toplevel();
+
+        dependent.frame(|ctx| clicks.read(ctx).len());
+        clicks.emit(1);
+
+        // this run doesn't look at the events
+        dependent.frame(|_| {});
+        clicks.emit(2);
+
+        assert_eq!(dependent.frame(|ctx| clicks.read(ctx).to_vec()), [1, 2]);
+    }
+}
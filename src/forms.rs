@@ -0,0 +1,219 @@
+//! Input fields that parse and validate what was entered, and forms that combine them.
+//!
+//! ```
+//! use fluorine::{forms::{Field, Form}, Dependent};
+//!
+//! let mut age = Field::new(String::new(), |input: &String| input.parse::<u32>());
+//! let mut name = Field::new(String::new(), |input: &String| {
+//!     if input.is_empty() { Err("required") } else { Ok(input.clone()) }
+//! });
+//! let mut form = Form::new();
+//!
+//! let dependent = Dependent::toplevel();
+//! let ctx = &dependent.ctx();
+//!
+//! assert_eq!(form.invalid(ctx, &mut [&mut age, &mut name]), [0, 1]);
+//!
+//! age.set_input("42".to_string());
+//! name.set_input("Ada".to_string());
+//!
+//! assert!(form.is_valid(ctx, &mut [&mut age, &mut name]));
+//! assert_eq!(age.value(ctx), Ok(&42));
+//! ```
+
+use std::fmt;
+
+use crate::{Rx, RxCell, RxCtx, RxFn};
+
+/// A single input. The raw input (the text in a text field, for example) is kept as it was
+/// entered, and the parsed value is derived from it.
+///
+/// A field is dirty when its input differs from the one it started with, and touched once
+/// [`Field::touch`] was called (usually when it loses focus), which is useful for only showing
+/// errors after the user is done with a field.
+pub struct Field<I, T, E> {
+    input: Rx<I>,
+    initial: I,
+    touched: RxCell<bool>,
+    parsed: RxFn<(), Result<T, E>>,
+    parse: Parse<I, T, E>,
+}
+
+type Parse<I, T, E> = Box<dyn Fn(&I) -> Result<T, E>>;
+
+impl<I: fmt::Debug, T: fmt::Debug, E: fmt::Debug> fmt::Debug for Field<I, T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Field")
+            .field("input", &self.input)
+            .field("initial", &self.initial)
+            .field("touched", &self.touched)
+            .field("parsed", &self.parsed)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<I: Clone + PartialEq, T, E> Field<I, T, E> {
+    pub fn new(initial: I, parse: impl Fn(&I) -> Result<T, E> + 'static) -> Self {
+        Field {
+            input: Rx::new(initial.clone()),
+            initial,
+            touched: RxCell::new(false),
+            parsed: RxFn::new(),
+            parse: Box::new(parse),
+        }
+    }
+
+    pub fn input(&self, ctx: &RxCtx) -> &I {
+        self.input.get(ctx)
+    }
+
+    #[track_caller]
+    pub fn set_input(&mut self, input: I) {
+        self.input.set_if_changed(input);
+    }
+
+    /// Only parses the input again if it has changed.
+    pub fn value(&mut self, ctx: &RxCtx) -> Result<&T, &E> {
+        let input = &self.input;
+        let parse = &self.parse;
+
+        self.parsed
+            .call(ctx, (), |ctx, ()| parse(input.get(ctx)))
+            .as_ref()
+    }
+
+    pub fn error(&mut self, ctx: &RxCtx) -> Option<&E> {
+        self.value(ctx).err()
+    }
+
+    pub fn is_dirty(&self, ctx: &RxCtx) -> bool {
+        *self.input.get(ctx) != self.initial
+    }
+
+    pub fn touched(&self, ctx: &RxCtx) -> bool {
+        self.touched.get(ctx)
+    }
+
+    #[track_caller]
+    pub fn touch(&self) {
+        self.touched.set(true);
+    }
+
+    /// Goes back to the initial input and forgets that the field was touched.
+    #[track_caller]
+    pub fn reset(&mut self) {
+        self.input.set_if_changed(self.initial.clone());
+        self.touched.set(false);
+    }
+}
+
+/// What a [`Form`] needs to know about its fields, so that fields with different types can be
+/// combined.
+pub trait FormField {
+    fn is_valid(&mut self, ctx: &RxCtx) -> bool;
+
+    fn is_dirty(&self, ctx: &RxCtx) -> bool;
+
+    fn touch(&self);
+}
+
+impl<I: Clone + PartialEq, T, E> FormField for Field<I, T, E> {
+    fn is_valid(&mut self, ctx: &RxCtx) -> bool {
+        self.value(ctx).is_ok()
+    }
+
+    fn is_dirty(&self, ctx: &RxCtx) -> bool {
+        Field::is_dirty(self, ctx)
+    }
+
+    fn touch(&self) {
+        Field::touch(self);
+    }
+}
+
+/// Combines the state of fields. The fields are passed in on every call like the closure of an
+/// [`RxFn`], so they can stay wherever they are stored, but it has to be the same fields in the
+/// same order every time. They're always passed as `&mut [&mut dyn FormField]`, so the same list
+/// works for every method.
+#[derive(Debug, Default)]
+pub struct Form {
+    invalid: RxFn<(), Vec<usize>>,
+    dirty: RxFn<(), bool>,
+}
+
+impl Form {
+    pub fn new() -> Self {
+        Form::default()
+    }
+
+    /// The indices of the fields that currently don't parse.
+    pub fn invalid(&mut self, ctx: &RxCtx, fields: &mut [&mut dyn FormField]) -> &[usize] {
+        self.invalid.call(ctx, (), |ctx, ()| {
+            fields
+                .iter_mut()
+                .enumerate()
+                .filter_map(|(i, field)| (!field.is_valid(ctx)).then_some(i))
+                .collect()
+        })
+    }
+
+    pub fn is_valid(&mut self, ctx: &RxCtx, fields: &mut [&mut dyn FormField]) -> bool {
+        self.invalid(ctx, fields).is_empty()
+    }
+
+    /// Whether any of the fields is dirty.
+    pub fn is_dirty(&mut self, ctx: &RxCtx, fields: &mut [&mut dyn FormField]) -> bool {
+        *self.dirty.call(ctx, (), |ctx, ()| {
+            fields.iter().any(|field| field.is_dirty(ctx))
+        })
+    }
+
+    /// Touches all the fields, for example when the user tries to submit the form, so that all
+    /// the errors get shown.
+    pub fn touch_all(fields: &mut [&mut dyn FormField]) {
+        for field in fields {
+            field.touch();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::*;
+    use crate::Dependent;
+
+    #[test]
+    fn test_form() {
+        let times_parsed = Rc::new(Cell::new(0));
+        let mut age = Field::new("1".to_string(), {
+            let times_parsed = times_parsed.clone();
+            move |input: &String| {
+                times_parsed.set(times_parsed.get() + 1);
+                input.parse::<u8>()
+            }
+        });
+        let mut form = Form::new();
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert!(form.is_valid(ctx, &mut [&mut age]));
+        assert!(!form.is_dirty(ctx, &mut [&mut age]));
+
+        age.set_input("1000".to_string());
+        assert!(!form.is_valid(ctx, &mut [&mut age]));
+        assert!(form.is_dirty(ctx, &mut [&mut age]));
+        assert!(age.error(ctx).is_some());
+        assert_eq!(times_parsed.get(), 2);
+
+        assert!(!age.touched(ctx));
+        Form::touch_all(&mut [&mut age]);
+        assert!(age.touched(ctx));
+
+        age.reset();
+        assert!(form.is_valid(ctx, &mut [&mut age]));
+        assert!(!age.touched(ctx));
+    }
+}
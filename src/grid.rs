@@ -0,0 +1,304 @@
+use crate::{Dependents, RxCtx};
+
+/// A reactive two-dimensional grid, for things like tables.
+///
+/// Every cell has its own list of dependents, and so does every row and every column. Reading a
+/// cell only subscribes to that cell, while [`RxGrid::track_row`] and [`RxGrid::track_col`]
+/// subscribe to everything in a row or a column. Inserting or removing rows and columns
+/// invalidates the readers of all the cells that end up at a different position.
+#[derive(Debug)]
+pub struct RxGrid<T> {
+    // row-major
+    rows: Vec<Vec<GridCell<T>>>,
+    cols: usize,
+    row_dependents: Vec<Dependents>,
+    col_dependents: Vec<Dependents>,
+    // Tracked by anything that depends on the size of the grid.
+    structure: Dependents,
+}
+
+#[derive(Debug)]
+struct GridCell<T> {
+    value: T,
+    dependents: Dependents,
+}
+
+impl<T> GridCell<T> {
+    fn new(value: T) -> Self {
+        GridCell {
+            value,
+            dependents: Dependents::new(),
+        }
+    }
+}
+
+impl<T> Default for RxGrid<T> {
+    fn default() -> Self {
+        RxGrid::new()
+    }
+}
+
+impl<T> RxGrid<T> {
+    pub fn new() -> Self {
+        RxGrid {
+            rows: Vec::new(),
+            cols: 0,
+            row_dependents: Vec::new(),
+            col_dependents: Vec::new(),
+            structure: Dependents::new(),
+        }
+    }
+
+    pub fn from_fn(rows: usize, cols: usize, mut f: impl FnMut(usize, usize) -> T) -> Self {
+        RxGrid {
+            rows: (0..rows)
+                .map(|row| (0..cols).map(|col| GridCell::new(f(row, col))).collect())
+                .collect(),
+            cols,
+            row_dependents: (0..rows).map(|_| Dependents::new()).collect(),
+            col_dependents: (0..cols).map(|_| Dependents::new()).collect(),
+            structure: Dependents::new(),
+        }
+    }
+
+    /// Only gets invalidated when rows or columns are inserted or removed.
+    pub fn rows(&self, ctx: &RxCtx) -> usize {
+        self.structure.track(ctx);
+
+        self.rows.len()
+    }
+
+    /// Only gets invalidated when rows or columns are inserted or removed.
+    pub fn cols(&self, ctx: &RxCtx) -> usize {
+        self.structure.track(ctx);
+
+        self.cols
+    }
+
+    pub fn get(&self, ctx: &RxCtx, row: usize, col: usize) -> Option<&T> {
+        let Some(cell) = self.rows.get(row).and_then(|r| r.get(col)) else {
+            // The cell might exist later.
+            self.structure.track(ctx);
+
+            return None;
+        };
+
+        cell.dependents.track(ctx);
+
+        Some(&cell.value)
+    }
+
+    /// Subscribes to every change of a cell in the row, including the ones caused by inserting
+    /// or removing rows and columns.
+    pub fn track_row(&self, ctx: &RxCtx, row: usize) {
+        match self.row_dependents.get(row) {
+            Some(dependents) => dependents.track(ctx),
+            None => self.structure.track(ctx),
+        }
+    }
+
+    /// Like [`RxGrid::track_row`], but for a column.
+    pub fn track_col(&self, ctx: &RxCtx, col: usize) {
+        match self.col_dependents.get(col) {
+            Some(dependents) => dependents.track(ctx),
+            None => self.structure.track(ctx),
+        }
+    }
+
+    /// The values of a row. Subscribes like [`RxGrid::track_row`].
+    pub fn row(&self, ctx: &RxCtx, row: usize) -> Option<impl Iterator<Item = &T>> {
+        self.track_row(ctx, row);
+
+        Some(self.rows.get(row)?.iter().map(|cell| &cell.value))
+    }
+
+    /// The values of a column. Subscribes like [`RxGrid::track_col`].
+    pub fn col(&self, ctx: &RxCtx, col: usize) -> Option<impl Iterator<Item = &T>> {
+        self.track_col(ctx, col);
+
+        if col >= self.cols {
+            return None;
+        }
+
+        Some(self.rows.iter().map(move |row| &row[col].value))
+    }
+
+    #[track_caller]
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        let cell = self.rows.get_mut(row)?.get_mut(col)?;
+
+        cell.dependents.mark_dirty();
+        self.row_dependents[row].mark_dirty();
+        self.col_dependents[col].mark_dirty();
+
+        Some(&mut cell.value)
+    }
+
+    /// # Panics
+    ///
+    /// If `index` is out of bounds or `values` doesn't have one value per column.
+    #[track_caller]
+    pub fn insert_row(&mut self, index: usize, values: Vec<T>) {
+        assert_eq!(values.len(), self.cols, "wrong number of values for a row");
+        assert!(index <= self.rows.len(), "row index out of bounds");
+
+        self.moved_rows(index);
+
+        self.rows
+            .insert(index, values.into_iter().map(GridCell::new).collect());
+        self.row_dependents.insert(index, Dependents::new());
+    }
+
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    #[track_caller]
+    pub fn remove_row(&mut self, index: usize) -> Vec<T> {
+        assert!(index < self.rows.len(), "row index out of bounds");
+
+        self.moved_rows(index);
+
+        self.row_dependents.remove(index);
+        self.rows
+            .remove(index)
+            .into_iter()
+            .map(|cell| cell.value)
+            .collect()
+    }
+
+    /// # Panics
+    ///
+    /// If `index` is out of bounds or `values` doesn't have one value per row.
+    #[track_caller]
+    pub fn insert_col(&mut self, index: usize, values: Vec<T>) {
+        assert_eq!(
+            values.len(),
+            self.rows.len(),
+            "wrong number of values for a column"
+        );
+        assert!(index <= self.cols, "column index out of bounds");
+
+        self.moved_cols(index);
+
+        for (row, value) in self.rows.iter_mut().zip(values) {
+            row.insert(index, GridCell::new(value));
+        }
+
+        self.cols += 1;
+        self.col_dependents.insert(index, Dependents::new());
+    }
+
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    #[track_caller]
+    pub fn remove_col(&mut self, index: usize) -> Vec<T> {
+        assert!(index < self.cols, "column index out of bounds");
+
+        self.moved_cols(index);
+
+        self.cols -= 1;
+        self.col_dependents.remove(index);
+        self.rows
+            .iter_mut()
+            .map(|row| row.remove(index).value)
+            .collect()
+    }
+
+    /// Invalidates everything that depends on the rows starting at `index`, because they're
+    /// about to end up at a different position.
+    #[track_caller]
+    fn moved_rows(&self, index: usize) {
+        for (row, dependents) in self.rows[index..].iter().zip(&self.row_dependents[index..]) {
+            for cell in row {
+                cell.dependents.mark_dirty();
+            }
+
+            dependents.mark_dirty();
+        }
+
+        for dependents in &self.col_dependents {
+            dependents.mark_dirty();
+        }
+
+        self.structure.mark_dirty();
+    }
+
+    /// Like [`RxGrid::moved_rows`], but for columns.
+    #[track_caller]
+    fn moved_cols(&self, index: usize) {
+        for row in &self.rows {
+            for cell in &row[index..] {
+                cell.dependents.mark_dirty();
+            }
+        }
+
+        for dependents in &self.col_dependents[index..] {
+            dependents.mark_dirty();
+        }
+
+        for dependents in &self.row_dependents {
+            dependents.mark_dirty();
+        }
+
+        self.structure.mark_dirty();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::{Dependent, RxFn};
+
+    #[test]
+    fn test_grid() {
+        let mut grid = RxGrid::from_fn(2, 2, |row, col| row * 10 + col);
+
+        let cell_called = Cell::new(0);
+        let row_called = Cell::new(0);
+
+        let mut cell = RxFn::new();
+        let mut row_sum = RxFn::new();
+
+        let mut run = |ctx, grid: &RxGrid<usize>| -> (Option<usize>, usize) {
+            let c = *cell.call(ctx, (), |ctx, ()| {
+                cell_called.set(cell_called.get() + 1);
+                grid.get(ctx, 1, 1).copied()
+            });
+
+            let r = *row_sum.call(ctx, (), |ctx, ()| {
+                row_called.set(row_called.get() + 1);
+                grid.row(ctx, 0).unwrap().sum()
+            });
+
+            (c, r)
+        };
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(run(ctx, &grid), (Some(11), 1));
+
+        *grid.get_mut(1, 0).unwrap() = 5;
+
+        assert_eq!(run(ctx, &grid), (Some(11), 1));
+        assert_eq!((cell_called.get(), row_called.get()), (1, 1));
+
+        *grid.get_mut(0, 1).unwrap() = 2;
+
+        assert_eq!(run(ctx, &grid), (Some(11), 2));
+        assert_eq!((cell_called.get(), row_called.get()), (1, 2));
+
+        // moves the cell at (1, 1) down
+        grid.insert_row(1, vec![7, 8]);
+
+        assert_eq!(run(ctx, &grid), (Some(8), 2));
+        assert_eq!((cell_called.get(), row_called.get()), (2, 2));
+
+        assert_eq!(grid.remove_col(0), [0, 7, 5]);
+        assert_eq!(grid.cols(ctx), 1);
+        assert_eq!(run(ctx, &grid), (None, 2));
+    }
+}
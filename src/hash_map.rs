@@ -0,0 +1,246 @@
+use std::{cell::RefCell, collections::HashMap, hash::Hash};
+
+use crate::{Dependents, RxCtx};
+
+/// A reactive map where every key has its own list of dependents.
+///
+/// Reading a key only subscribes to that key, so mutating one entry doesn't invalidate readers of
+/// the others. Operations that change the set of keys additionally notify the dependents that
+/// looked at the map as a whole (through [`RxHashMap::keys`] or [`RxHashMap::len`]).
+#[derive(Debug)]
+pub struct RxHashMap<K, V> {
+    pub(crate) map: HashMap<K, V>,
+    // Keys can be tracked before they exist so that readers get notified once they're inserted.
+    // That's why this is separate from the values.
+    key_dependents: RefCell<HashMap<K, Dependents>>,
+    keys_dependents: Dependents,
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> Clone for RxHashMap<K, V> {
+    fn clone(&self) -> Self {
+        RxHashMap {
+            map: self.map.clone(),
+            key_dependents: RefCell::new(HashMap::new()),
+            keys_dependents: Dependents::new(),
+        }
+    }
+}
+
+impl<K: Clone + Eq + Hash, V> Default for RxHashMap<K, V> {
+    fn default() -> Self {
+        RxHashMap::new()
+    }
+}
+
+impl<K: Clone + Eq + Hash, V> RxHashMap<K, V> {
+    pub fn new() -> Self {
+        RxHashMap {
+            map: HashMap::new(),
+            key_dependents: RefCell::new(HashMap::new()),
+            keys_dependents: Dependents::new(),
+        }
+    }
+
+    fn track_key(&self, ctx: &RxCtx, key: &K) {
+        let mut key_dependents = self.key_dependents.borrow_mut();
+
+        let dependents = match key_dependents.get(key) {
+            Some(dependents) => dependents,
+            None => key_dependents.entry(key.clone()).or_default(),
+        };
+
+        dependents.track(ctx);
+    }
+
+    #[track_caller]
+    fn mark_key_dirty(&mut self, key: &K) {
+        let key_dependents = self.key_dependents.get_mut();
+
+        if let Some(dependents) = key_dependents.get(key) {
+            dependents.mark_dirty();
+
+            // nobody is interested in this key anymore
+            if dependents.is_empty() {
+                key_dependents.remove(key);
+            }
+        }
+    }
+
+    /// Like [`crate::Dependents::compact`], but also forgets about keys nobody is interested in
+    /// anymore.
+    pub fn compact(&mut self) {
+        self.key_dependents.get_mut().retain(|_, dependents| {
+            dependents.compact();
+
+            !dependents.is_empty()
+        });
+
+        self.keys_dependents.compact();
+    }
+
+    pub fn get(&self, ctx: &RxCtx, key: &K) -> Option<&V> {
+        self.track_key(ctx, key);
+
+        self.map.get(key)
+    }
+
+    pub fn contains_key(&self, ctx: &RxCtx, key: &K) -> bool {
+        self.track_key(ctx, key);
+
+        self.map.contains_key(key)
+    }
+
+    /// See [`crate::Rx::get_untracked`].
+    #[track_caller]
+    pub fn get_untracked(&self, key: &K) -> Option<&V> {
+        crate::hazards::check_untracked_read();
+
+        self.map.get(key)
+    }
+
+    pub fn get_untracked_intentional(&self, key: &K) -> Option<&V> {
+        self.map.get(key)
+    }
+
+    /// Only gets invalidated when keys are inserted or removed, not when values change.
+    pub fn keys(&self, ctx: &RxCtx) -> impl Iterator<Item = &K> {
+        self.keys_dependents.track(ctx);
+
+        self.map.keys()
+    }
+
+    /// Only gets invalidated when keys are inserted or removed, not when values change.
+    pub fn len(&self, ctx: &RxCtx) -> usize {
+        self.keys_dependents.track(ctx);
+
+        self.map.len()
+    }
+
+    pub fn is_empty(&self, ctx: &RxCtx) -> bool {
+        self.len(ctx) == 0
+    }
+
+    #[track_caller]
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        // There's nothing to mutate, so the readers of the key don't have to know.
+        if !self.map.contains_key(key) {
+            return None;
+        }
+
+        self.mark_key_dirty(key);
+
+        self.map.get_mut(key)
+    }
+
+    #[track_caller]
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.mark_key_dirty(&key);
+
+        let old = self.map.insert(key, value);
+
+        if old.is_none() {
+            self.keys_dependents.mark_dirty();
+        }
+
+        old
+    }
+
+    #[track_caller]
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let old = self.map.remove(key);
+
+        if old.is_some() {
+            self.mark_key_dirty(key);
+            self.keys_dependents.mark_dirty();
+        }
+
+        old
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::{Dependent, RxFn};
+
+    #[test]
+    fn test_per_key_tracking() {
+        let mut map = RxHashMap::new();
+        map.insert(1, 10);
+        map.insert(2, 20);
+
+        let times_called = Cell::new(0);
+
+        let mut f = RxFn::new();
+        let mut get = |ctx, map: &RxHashMap<u32, u32>, key: u32| -> Option<u32> {
+            *f.call(ctx, key, |ctx, key| {
+                times_called.set(times_called.get() + 1);
+                map.get(ctx, key).copied()
+            })
+        };
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(get(ctx, &map, 1), Some(10));
+        assert_eq!(times_called.get(), 1);
+
+        *map.get_mut(&2).unwrap() = 21;
+        map.insert(3, 30);
+
+        assert_eq!(get(ctx, &map, 1), Some(10));
+        assert_eq!(times_called.get(), 1);
+
+        map.remove(&1);
+
+        assert_eq!(get(ctx, &map, 1), None);
+        assert_eq!(times_called.get(), 2);
+
+        map.insert(1, 11);
+
+        assert_eq!(get(ctx, &map, 1), Some(11));
+        assert_eq!(times_called.get(), 3);
+
+        map.remove(&1);
+        assert_eq!(get(ctx, &map, 1), None);
+        assert_eq!(times_called.get(), 4);
+
+        assert!(map.get_mut(&1).is_none());
+        assert_eq!(get(ctx, &map, 1), None);
+        assert_eq!(times_called.get(), 4);
+    }
+
+    #[test]
+    fn test_keys_tracking() {
+        let mut map = RxHashMap::new();
+        map.insert(1, 10);
+
+        let times_called = Cell::new(0);
+
+        let mut f = RxFn::new();
+        let mut len = |ctx, map: &RxHashMap<u32, u32>| -> usize {
+            *f.call(ctx, (), |ctx, ()| {
+                times_called.set(times_called.get() + 1);
+                map.len(ctx)
+            })
+        };
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(len(ctx, &map), 1);
+
+        *map.get_mut(&1).unwrap() = 11;
+        map.insert(1, 12);
+
+        assert_eq!(len(ctx, &map), 1);
+        assert_eq!(times_called.get(), 1);
+
+        map.insert(2, 20);
+
+        assert_eq!(len(ctx, &map), 2);
+        assert_eq!(times_called.get(), 2);
+    }
+}
@@ -0,0 +1,169 @@
+//! Checks for patterns that are almost always bugs, like reading state without tracking it while
+//! a computation is running. They only run in builds with debug assertions.
+//!
+//! A computation here means the closure of an [`crate::RxFn`] (or anything built on it). Code that
+//! runs in [`crate::Dependent::frame`] or [`crate::implicit::with_ctx`], like event handlers, is
+//! allowed to do these things.
+
+use std::{cell::Cell, panic::Location};
+
+/// What happens when one of the checks finds something.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Policy {
+    Ignore,
+    /// Prints a warning to stderr (or emits a tracing event with the `tracing` feature).
+    #[default]
+    Warn,
+    Panic,
+}
+
+thread_local! {
+    static UNTRACKED_READ: Cell<Policy> = const { Cell::new(Policy::Warn) };
+    static WRITE: Cell<Policy> = const { Cell::new(Policy::Warn) };
+    // How many computations are running on the current thread. This is separate from the stack of
+    // implicit contexts because that one also has frames on it.
+    static COMPUTING: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Runs `f` as a computation, so that the checks apply inside of it.
+pub(crate) fn computing<R>(f: impl FnOnce() -> R) -> R {
+    struct Guard;
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            COMPUTING.set(COMPUTING.get() - 1);
+        }
+    }
+
+    COMPUTING.set(COMPUTING.get() + 1);
+    let _guard = Guard;
+
+    f()
+}
+
+fn is_computing() -> bool {
+    COMPUTING.get() > 0
+}
+
+/// Sets what happens when something like [`crate::Rx::get_untracked`] gets called while a
+/// computation is running on the current thread. Methods like
+/// [`crate::Rx::get_untracked_intentional`] are never reported.
+pub fn set_untracked_read_policy(policy: Policy) {
+    UNTRACKED_READ.set(policy);
+}
+
+#[track_caller]
+pub(crate) fn check_untracked_read() {
+    if cfg!(debug_assertions) && is_computing() {
+        report(
+            UNTRACKED_READ.get(),
+            "state was read without tracking it while a computation was running",
+        );
+    }
+}
+
+/// Sets what happens when state gets mutated while a computation is running on the current
+/// thread. This can lead to computations getting invalidated while they run or missing the change.
+pub fn set_write_policy(policy: Policy) {
+    WRITE.set(policy);
+}
+
+#[track_caller]
+pub(crate) fn check_write() {
+    if cfg!(debug_assertions) && is_computing() {
+        report(
+            WRITE.get(),
+            "state was mutated while a computation was running",
+        );
+    }
+}
+
+#[track_caller]
+fn report(policy: Policy, message: &str) {
+    let location = Location::caller();
+
+    match policy {
+        Policy::Ignore => {}
+        Policy::Warn => {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(%location, "{}", message);
+
+            #[cfg(not(feature = "tracing"))]
+            eprintln!("fluorine: {} at {}", message, location);
+        }
+        Policy::Panic => panic!("{} at {}", message, location),
+    }
+}
+
+#[cfg(all(test, debug_assertions))]
+mod tests {
+    use super::*;
+    use crate::{Dependent, Rx, RxFn};
+
+    #[test]
+    #[should_panic(expected = "state was read without tracking it")]
+    fn test_untracked_read() {
+        set_untracked_read_policy(Policy::Panic);
+
+        let a = Rx::new(1);
+        let mut f = RxFn::new();
+
+        let dependent = Dependent::toplevel();
+
+        // fine outside of computations
+        a.get_untracked();
+
+        f.call(&dependent.ctx(), (), |_, ()| {
+            a.get_untracked_intentional();
+            a.get_untracked();
+        });
+    }
+
+    #[test]
+    fn test_untracked_read_in_frame() {
+        set_untracked_read_policy(Policy::Panic);
+
+        let a = Rx::new(1);
+        let (_, write) = crate::rx_pair(1);
+
+        let dependent = Dependent::toplevel();
+
+        dependent.frame(|_| {
+            a.get_untracked();
+            write.get_untracked();
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "state was mutated while a computation was running")]
+    fn test_write() {
+        set_write_policy(Policy::Panic);
+
+        let mut a = Rx::new(1);
+        let mut f = RxFn::new();
+
+        let dependent = Dependent::toplevel();
+
+        a.set(2);
+
+        f.call(&dependent.ctx(), (), |_, ()| a.set(3));
+    }
+
+    #[test]
+    fn test_write_in_frame() {
+        set_write_policy(Policy::Panic);
+
+        let mut a = Rx::new(1);
+        let mut f = RxFn::new();
+
+        let dependent = Dependent::toplevel();
+
+        // like an event handler that runs during a frame
+        dependent.frame(|ctx| {
+            f.call(ctx, (), |ctx, ()| *a.get(ctx));
+            a.set(2);
+        });
+
+        crate::implicit::with_ctx(&dependent.ctx(), || a.set(3));
+    }
+}
@@ -0,0 +1,79 @@
+use std::mem;
+
+use crate::{Rx, RxCtx};
+
+/// Like [`Rx`], but also keeps the value it had before the latest change, for example to render
+/// a diff or to react to a transition from one value to another.
+#[derive(Debug, Clone)]
+pub struct RxWithHistory<T> {
+    current: Rx<T>,
+    previous: Option<T>,
+}
+
+impl<T: Clone> RxWithHistory<T> {
+    pub fn new(value: T) -> Self {
+        RxWithHistory {
+            current: Rx::new(value),
+            previous: None,
+        }
+    }
+
+    pub fn get(&self, ctx: &RxCtx) -> &T {
+        self.current.get(ctx)
+    }
+
+    /// The value before the latest change, or `None` if it has never been changed. This changes
+    /// together with the current value, so both are tracked the same way.
+    pub fn previous(&self, ctx: &RxCtx) -> Option<&T> {
+        self.current.get(ctx);
+
+        self.previous.as_ref()
+    }
+
+    #[track_caller]
+    pub fn get_untracked(&self) -> &T {
+        self.current.get_untracked()
+    }
+
+    #[track_caller]
+    pub fn set(&mut self, value: T) {
+        let previous = mem::replace(self.current.get_mut(), value);
+
+        self.previous = Some(previous);
+    }
+
+    /// Changes the value in place. The value from before gets cloned to be kept as the previous
+    /// one.
+    #[track_caller]
+    pub fn update(&mut self, f: impl FnOnce(&mut T)) {
+        let value = self.current.get_mut();
+
+        self.previous = Some(value.clone());
+        f(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Dependent;
+
+    #[test]
+    fn test_history() {
+        let mut a = RxWithHistory::new(1);
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(a.previous(ctx), None);
+        dependent.take_dirty();
+
+        a.set(2);
+        assert!(dependent.take_dirty());
+        assert_eq!((a.previous(ctx), a.get(ctx)), (Some(&1), &2));
+
+        a.update(|a| *a *= 10);
+        assert!(dependent.take_dirty());
+        assert_eq!((a.previous(ctx), a.get(ctx)), (Some(&2), &20));
+    }
+}
@@ -0,0 +1,80 @@
+//! An opt-in way of reading reactive state without passing an [`RxCtx`] around, for retrofitting
+//! reactivity into existing code. See [`with_ctx`].
+
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{Dependent, RxCtx};
+
+thread_local! {
+    static CURRENT: RefCell<Vec<Rc<Dependent>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Makes `ctx` the current context while `f` runs, so that it gets picked up by methods like
+/// [`crate::Rx::read`]. Computations like [`crate::RxFn`] make their own context the current one
+/// while they run.
+pub fn with_ctx<R>(ctx: &RxCtx, f: impl FnOnce() -> R) -> R {
+    enter(ctx.dependent, f)
+}
+
+pub(crate) fn enter<R>(dependent: &Rc<Dependent>, f: impl FnOnce() -> R) -> R {
+    struct Guard;
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            CURRENT.with_borrow_mut(|current| current.pop());
+        }
+    }
+
+    CURRENT.with_borrow_mut(|current| current.push(dependent.clone()));
+    let _guard = Guard;
+
+    f()
+}
+
+/// Calls `f` with the current context if there is one.
+pub fn current_ctx<R>(f: impl FnOnce(&RxCtx) -> R) -> Option<R> {
+    let dependent = CURRENT.with_borrow(|current| current.last().cloned())?;
+
+    Some(f(&dependent.ctx()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::{Rx, RxFn};
+
+    #[test]
+    fn test_implicit_ctx() {
+        let mut a = Rx::new(1);
+        let times_called = Cell::new(0);
+
+        // doesn't take a ctx
+        fn double(a: &Rx<u32>) -> u32 {
+            a.read() * 2
+        }
+
+        let mut f = RxFn::new();
+        let mut run = |ctx, a: &Rx<u32>| -> u32 {
+            *f.call(ctx, (), |_, ()| {
+                times_called.set(times_called.get() + 1);
+                double(a)
+            })
+        };
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(with_ctx(ctx, || run(ctx, &a)), 2);
+        assert_eq!(run(ctx, &a), 2);
+        assert_eq!(times_called.get(), 1);
+
+        a.set(2);
+
+        assert_eq!(run(ctx, &a), 4);
+        assert_eq!(times_called.get(), 2);
+
+        assert!(current_ctx(|_| ()).is_none());
+    }
+}
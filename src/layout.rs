@@ -0,0 +1,168 @@
+//! Helpers for caching layout in retained widget trees.
+//!
+//! A widget measures itself with a [`MeasureCache`], and the measure closure of a parent calls the
+//! ones of its children. That way a child whose size changes invalidates the measurements of its
+//! ancestors, while everything else keeps its cached size.
+
+use crate::{RxCtx, RxFn};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Size {
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Size {
+    pub fn new(width: f64, height: f64) -> Self {
+        Size { width, height }
+    }
+
+    fn approx_eq(&self, other: &Size, epsilon: f64) -> bool {
+        (self.width - other.width).abs() <= epsilon && (self.height - other.height).abs() <= epsilon
+    }
+}
+
+/// The space a widget gets to lay itself out in.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Constraints {
+    pub min: Size,
+    pub max: Size,
+}
+
+impl Constraints {
+    pub fn new(min: Size, max: Size) -> Self {
+        Constraints { min, max }
+    }
+
+    /// Only allows exactly `size`.
+    pub fn tight(size: Size) -> Self {
+        Constraints {
+            min: size,
+            max: size,
+        }
+    }
+
+    fn approx_eq(&self, other: &Constraints, epsilon: f64) -> bool {
+        self.min.approx_eq(&other.min, epsilon) && self.max.approx_eq(&other.max, epsilon)
+    }
+}
+
+/// An [`RxFn`] for measuring a widget. Constraints and sizes that differ by at most `epsilon` are
+/// considered the same, so rounding noise (during a resize, for example) doesn't cause a
+/// measurement and doesn't count as a changed size.
+#[derive(Debug)]
+pub struct MeasureCache {
+    rx_fn: RxFn<Constraints, Size>,
+    epsilon: f64,
+    hits: u64,
+    misses: u64,
+}
+
+impl Default for MeasureCache {
+    fn default() -> Self {
+        MeasureCache::new()
+    }
+}
+
+impl MeasureCache {
+    /// Uses an epsilon of `1e-6`.
+    pub fn new() -> Self {
+        MeasureCache::with_epsilon(1e-6)
+    }
+
+    pub fn with_epsilon(epsilon: f64) -> Self {
+        MeasureCache {
+            rx_fn: RxFn::new(),
+            epsilon,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Only calls `measure` if the constraints or something it depends on (like the sizes of the
+    /// children) have changed since the last time.
+    pub fn measure(
+        &mut self,
+        ctx: &RxCtx,
+        constraints: Constraints,
+        mut measure: impl FnMut(&RxCtx, &Constraints) -> Size,
+    ) -> Size {
+        let epsilon = self.epsilon;
+        let mut measured = false;
+
+        let size = *self.rx_fn.call_impl(
+            ctx,
+            constraints,
+            |ctx, constraints| {
+                measured = true;
+                measure(ctx, constraints)
+            },
+            |a, b| a.approx_eq(b, epsilon),
+            |a, b| a.approx_eq(b, epsilon),
+        );
+
+        if measured {
+            self.misses += 1;
+        } else {
+            self.hits += 1;
+        }
+
+        size
+    }
+
+    /// How many calls of [`MeasureCache::measure`] returned the cached size.
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// How many calls of [`MeasureCache::measure`] had to measure.
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.hits = 0;
+        self.misses = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Dependent, Rx};
+
+    #[test]
+    fn test_measure_cache() {
+        let mut text_len = Rx::new(10.);
+
+        let mut parent = MeasureCache::new();
+        let mut child = MeasureCache::new();
+
+        let mut measure = |ctx, text_len: &Rx<f64>, max_width: f64| -> Size {
+            let constraints = Constraints::new(Size::default(), Size::new(max_width, 100.));
+
+            parent.measure(ctx, constraints, |ctx, constraints| {
+                let inner = child.measure(ctx, *constraints, |ctx, constraints| {
+                    let width = text_len.get(ctx).min(constraints.max.width);
+                    Size::new(width, 20.)
+                });
+
+                Size::new(inner.width + 4., inner.height + 4.)
+            })
+        };
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(measure(ctx, &text_len, 50.), Size::new(14., 24.));
+        assert_eq!(measure(ctx, &text_len, 50. + 1e-9), Size::new(14., 24.));
+
+        text_len.set(60.);
+        assert_eq!(measure(ctx, &text_len, 50.), Size::new(54., 24.));
+        text_len.set(70.);
+        assert_eq!(measure(ctx, &text_len, 50.), Size::new(54., 24.));
+
+        assert_eq!((child.hits(), child.misses()), (0, 3));
+        assert_eq!((parent.hits(), parent.misses()), (1, 3));
+    }
+}
@@ -0,0 +1,205 @@
+//! Finding computations that are still alive but no longer used, like the memos of a UI subtree
+//! that was removed but whose state got stuck in some long-lived collection, and keeping track of
+//! how much memory the dependency graph takes up. Only available with the `leaks` feature, which
+//! makes every [`Dependent`] register itself on creation.
+
+use std::{
+    cell::RefCell,
+    collections::HashSet,
+    mem,
+    rc::{Rc, Weak},
+};
+
+use crate::{Dependency, Dependent, DependentsInner, Edge};
+
+thread_local! {
+    static LIVE: RefCell<Vec<Weak<Dependent>>> = const { RefCell::new(Vec::new()) };
+}
+
+pub(crate) fn register(dependent: &Rc<Dependent>) {
+    LIVE.with_borrow_mut(|live| {
+        // Only filter out dropped ones when the vector would grow anyway, so registering stays
+        // cheap.
+        if live.len() == live.capacity() {
+            live.retain(|d| d.strong_count() > 0);
+        }
+
+        live.push(Rc::downgrade(dependent));
+    });
+}
+
+/// All dependents on the current thread that still exist.
+pub fn live() -> Vec<Rc<Dependent>> {
+    LIVE.with_borrow_mut(|live| {
+        live.retain(|d| d.strong_count() > 0);
+
+        live.iter().filter_map(Weak::upgrade).collect()
+    })
+}
+
+/// A dependent that exists but can't be reached from any of the roots.
+#[derive(Debug)]
+pub struct Leak {
+    pub dependent: Rc<Dependent>,
+    /// Whether it's waiting for a rerun that is never going to happen.
+    pub dirty: bool,
+    /// What it's still registered as a dependent of. Each of these keeps an entry for it until it
+    /// gets mutated or compacted.
+    pub subscriptions: Vec<Dependency>,
+}
+
+/// Finds the live dependents that `roots` don't (transitively) depend on. Everything that is still
+/// in use has to be reachable from one of the roots, so those are usually the toplevels of the
+/// application.
+pub fn find_leaks(roots: &[Rc<Dependent>]) -> Vec<Leak> {
+    let mut reachable: HashSet<*const Dependent> = roots.iter().map(Rc::as_ptr).collect();
+    let mut worklist = roots.to_vec();
+
+    while let Some(dependent) = worklist.pop() {
+        for computation in dependent
+            .dependencies()
+            .iter()
+            .filter_map(Dependency::computation)
+        {
+            if reachable.insert(Rc::as_ptr(&computation)) {
+                worklist.push(computation);
+            }
+        }
+    }
+
+    live()
+        .into_iter()
+        .filter(|d| !reachable.contains(&Rc::as_ptr(d)))
+        .map(|dependent| Leak {
+            dirty: dependent.dirty(),
+            subscriptions: dependent.dependencies(),
+            dependent,
+        })
+        .collect()
+}
+
+/// The memory taken up by the computations on the current thread. State like an [`crate::Rx`]
+/// isn't registered anywhere, so its lists of dependents aren't included.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryReport {
+    pub dependents: usize,
+    /// Entries for what the dependents read during their latest runs.
+    pub dependencies: usize,
+    /// Entries in the lists of what depends on the results of the dependents, including ones that
+    /// are stale and haven't been filtered out yet.
+    pub dependent_edges: usize,
+    /// An estimate of the bytes all of that takes up, counting what was allocated and not just
+    /// what is in use.
+    pub bytes: usize,
+}
+
+pub fn memory_report() -> MemoryReport {
+    let mut report = MemoryReport::default();
+
+    for dependent in live() {
+        let dependencies = dependent.dependencies.borrow();
+        let pending = dependent.pending.borrow();
+        let list = dependent.dependents.0.list.borrow();
+
+        report.dependents += 1;
+        report.dependencies += dependencies.len();
+        report.dependent_edges += list.len();
+
+        // both live behind an Rc, which adds two counters
+        report.bytes += mem::size_of::<Dependent>() + mem::size_of::<DependentsInner>();
+        report.bytes += 4 * mem::size_of::<usize>();
+
+        report.bytes += dependencies.capacity() * mem::size_of::<Weak<DependentsInner>>();
+        report.bytes += pending.capacity() * mem::size_of::<Weak<Dependent>>();
+
+        if list.spilled() {
+            report.bytes += list.capacity() * mem::size_of::<Edge>();
+        }
+    }
+
+    report
+}
+
+/// Filters out stale entries everywhere in the dependency graph of the current thread and frees
+/// the memory that isn't needed anymore. See [`crate::Dependents::compact`].
+pub fn shrink() {
+    for dependent in live() {
+        dependent.dependents.compact();
+
+        let mut dependencies = dependent.dependencies.borrow_mut();
+        dependencies.retain(|d| d.strong_count() > 0);
+        dependencies.shrink_to_fit();
+
+        dependent.pending.borrow_mut().shrink_to_fit();
+    }
+
+    LIVE.with_borrow_mut(|live| live.shrink_to_fit());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Rx, RxFn};
+
+    #[test]
+    fn test_find_leaks() {
+        let mut a = Rx::new_named("a", 1);
+        let mut used = RxFn::new_named("used");
+        let mut removed = RxFn::new_named("removed");
+
+        let roots = [Dependent::toplevel_named("root")];
+        let ctx = &roots[0].ctx();
+
+        used.call(ctx, (), |ctx, ()| *a.get(ctx));
+        removed.call(ctx, (), |ctx, ()| *a.get(ctx) + 1);
+
+        assert!(find_leaks(&roots).is_empty());
+
+        // the next frame doesn't use `removed` anymore, but it's kept alive
+        roots[0].frame(|ctx| {
+            used.call(ctx, (), |ctx, ()| *a.get(ctx));
+        });
+        a.set(2);
+
+        let leaks = find_leaks(&roots);
+
+        assert_eq!(leaks.len(), 1);
+        assert_eq!(leaks[0].dependent.name(), Some("removed"));
+        assert!(leaks[0].dirty);
+        assert_eq!(leaks[0].subscriptions[0].name(), Some("a"));
+
+        drop(leaks);
+        drop(removed);
+        assert!(find_leaks(&roots).is_empty());
+    }
+
+    #[test]
+    fn test_memory_report() {
+        let a = Rx::new(1);
+        let mut f = RxFn::new();
+        let mut g = RxFn::new();
+
+        let root = Dependent::toplevel();
+
+        root.frame(|ctx| {
+            g.call(ctx, (), |ctx, ()| *f.call(ctx, (), |ctx, ()| *a.get(ctx)));
+        });
+
+        let report = memory_report();
+        assert_eq!(report.dependents, 3);
+        assert_eq!(report.dependencies, 3);
+        assert_eq!(report.dependent_edges, 2);
+
+        // `g` still has an entry for `root`, which doesn't use it anymore
+        root.frame(|_| {});
+        assert_eq!(memory_report().dependencies, 2);
+        assert_eq!(memory_report().dependent_edges, 2);
+
+        shrink();
+
+        let shrunk = memory_report();
+        assert_eq!(shrunk.dependencies, 2);
+        assert_eq!(shrunk.dependent_edges, 1);
+        assert!(shrunk.bytes < report.bytes);
+    }
+}
@@ -1,84 +1,280 @@
 use std::{
-    cell::{Cell, RefCell},
+    borrow::Cow,
+    cell::{Cell, OnceCell, RefCell},
+    collections::HashSet,
+    fmt,
     rc::{Rc, Weak},
 };
 
+use smallvec::SmallVec;
+
+mod background;
+#[cfg(feature = "capi")]
+pub mod capi;
+mod changed;
+#[cfg(feature = "futures")]
+mod changes;
+mod clock;
+pub mod combinators;
+pub mod debug;
+mod deque;
+mod double_rx;
+#[cfg(feature = "egui")]
+pub mod egui;
+mod events;
+pub mod forms;
+mod grid;
+mod hash_map;
+pub mod hazards;
+mod history;
+pub mod implicit;
+pub mod layout;
+#[cfg(feature = "leaks")]
+pub mod leaks;
+mod lru_rx_fn;
+mod maybe_rx;
+mod memo;
+mod patch_rx;
+mod projection;
+mod rate_limit;
+mod rc_rx;
+mod read_rx;
+mod receiver;
+mod recursive_memo;
+mod resource;
+mod runtime;
+mod rx_cell;
+mod rx_fn_cell;
+mod rx_fn_map;
+mod rx_pair;
+mod rx_ref_cell;
+#[cfg(feature = "serde")]
+mod serde_impls;
+mod slot_map;
+mod source;
+#[cfg(feature = "stats")]
+pub mod stats;
+#[cfg(feature = "sync")]
+pub mod sync;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+mod text;
+mod trigger;
+mod vec;
+mod watch;
+
+pub use background::{BackgroundRxFn, BackgroundState, CancelToken};
+pub use changed::Changed;
+#[cfg(feature = "futures")]
+pub use changes::Changes;
+pub use clock::RxClock;
+pub use deque::{DequeId, RxDeque};
+pub use double_rx::DoubleRx;
+pub use events::RxEvents;
+pub use grid::RxGrid;
+pub use hash_map::RxHashMap;
+pub use history::RxWithHistory;
+pub use lru_rx_fn::LruRxFn;
+pub use maybe_rx::MaybeRx;
+pub use memo::{Memo, RcMemo};
+pub use patch_rx::{Diffable, PatchRx};
+pub use projection::Projection;
+pub use rate_limit::{Debounced, Throttled};
+pub use rc_rx::RcRx;
+pub use read_rx::ReadRx;
+pub use receiver::RxReceiver;
+pub use recursive_memo::RecursiveMemo;
+pub use resource::{Resource, ResourceState};
+pub use runtime::{Effect, FlushError, Lane, Phase, Runtime};
+pub use rx_cell::RxCell;
+pub use rx_fn_cell::{CycleError, RxFnCell};
+pub use rx_fn_map::RxFnMap;
+pub use rx_pair::{rx_pair, ReadHandle, WriteHandle};
+pub use rx_ref_cell::{RxRefCell, RxRefMut};
+pub use slot_map::{RxSlotMap, SlotKey};
+pub use source::Source;
+pub use text::{RxText, TextRange};
+pub use trigger::Trigger;
+pub use vec::{FilterView, Fold, GroupBy, MapKeyed, Monoid, RxVec, SortedView, VecChange, VecId};
+pub use watch::Watcher;
+
+#[cfg(feature = "derive")]
+pub use fluorine_macros::Reactive;
+
 #[derive(Debug)]
 pub struct Rx<T> {
     value: T,
-    dependents: RefCell<Vec<(u64, Weak<Dependent>)>>,
+    dependents: Dependents,
+    #[cfg(feature = "futures")]
+    subscribers: changes::Subscribers<T>,
 }
 
 impl<T: Clone> Clone for Rx<T> {
     fn clone(&self) -> Self {
+        Rx::with_dependents(
+            self.value.clone(),
+            Dependents::with_name(self.dependents.0.name.clone()),
+        )
+    }
+}
+
+impl<T> Rx<T> {
+    fn with_dependents(value: T, dependents: Dependents) -> Self {
         Rx {
-            value: self.value.clone(),
-            dependents: RefCell::new(Vec::new()),
+            value,
+            dependents,
+            #[cfg(feature = "futures")]
+            subscribers: changes::Subscribers::new(),
+        }
+    }
+
+    fn value(&self) -> &T {
+        #[cfg(feature = "futures")]
+        self.subscribers.flush(&self.value);
+
+        &self.value
+    }
+
+    fn value_mut(&mut self) -> &mut T {
+        #[cfg(feature = "futures")]
+        {
+            self.subscribers.flush(&self.value);
+            self.subscribers.mark_stale();
         }
+
+        &mut self.value
+    }
+}
+
+// Publishes what the value was changed to through Rx::get_mut if nothing has accessed it since.
+#[cfg(feature = "futures")]
+impl<T> Drop for Rx<T> {
+    fn drop(&mut self) {
+        self.subscribers.flush(&self.value);
     }
 }
 
 impl<T: Clone> Rx<T> {
     pub fn new(value: T) -> Self {
-        Rx {
-            value,
-            dependents: RefCell::new(Vec::new()),
-        }
+        Rx::with_dependents(value, Dependents::new())
+    }
+
+    /// The name shows up in the debug output and the graph exported by [`debug::to_dot`].
+    pub fn new_named(name: impl Into<Cow<'static, str>>, value: T) -> Self {
+        Rx::with_dependents(value, Dependents::new_named(name))
     }
 
     pub fn get(&self, ctx: &RxCtx) -> &T {
-        let mut dependents = self.dependents.borrow_mut();
+        self.dependents.track(ctx);
 
-        let mut push = true;
+        self.value()
+    }
 
-        dependents.retain_mut(|(gen, d)| {
-            let Some(dependent) = d.upgrade() else {
-                // filter out dependents that no longer exist
-                return false;
-            };
+    /// Like [`Rx::get`], but uses the current context from [`implicit::with_ctx`].
+    ///
+    /// # Panics
+    ///
+    /// If there is no current context.
+    pub fn read(&self) -> &T {
+        implicit::current_ctx(|ctx| self.dependents.track(ctx))
+            .expect("Rx::read was called outside of implicit::with_ctx");
 
-            if Rc::ptr_eq(&dependent, ctx.dependent) {
-                *gen = ctx.dependent.generation.get();
-                push = false;
-            }
+        self.value()
+    }
 
-            true
-        });
+    /// In debug builds this gets reported if it happens while a computation is running, because
+    /// that's almost always a bug. See [`hazards::set_untracked_read_policy`].
+    #[track_caller]
+    pub fn get_untracked(&self) -> &T {
+        hazards::check_untracked_read();
 
-        if push {
-            dependents.push((ctx.dependent.generation.get(), Rc::downgrade(ctx.dependent)));
-        }
+        self.value()
+    }
 
-        &self.value
+    /// Like [`Rx::get_untracked`], but never gets reported.
+    pub fn get_untracked_intentional(&self) -> &T {
+        self.value()
     }
 
-    pub fn get_untracked(&self) -> &T {
-        &self.value
+    /// Everything that currently depends on this.
+    pub fn dependents(&self) -> Vec<Rc<Dependent>> {
+        self.dependents.dependents()
+    }
+
+    /// Resolves the next time this gets mutated.
+    pub fn changed(&self) -> Changed {
+        self.dependents.changed()
+    }
+
+    /// See [`Dependents::compact`].
+    pub fn compact(&self) {
+        self.dependents.compact();
+    }
+
+    /// Reads a part of the value through a [`Projection`] that compares it to what it was before.
+    /// Changes to other parts of the value only invalidate the readers of the projection until
+    /// the first one of them has checked that the part is still the same, like with
+    /// [`RxFn::call_with_eq`].
+    pub fn project<'p, O: Clone + PartialEq>(
+        &self,
+        ctx: &RxCtx,
+        projection: &'p mut Projection<O>,
+        f: impl Fn(&T) -> &O,
+    ) -> &'p O {
+        projection
+            .0
+            .call_with_eq(ctx, (), |ctx, ()| f(self.get(ctx)).clone())
     }
 
+    #[track_caller]
     pub fn get_mut(&mut self) -> &mut T {
-        fn mark_dirty(dependents: &RefCell<Vec<(u64, Weak<Dependent>)>>) {
-            dependents.borrow_mut().retain(|(gen, d)| {
-                let Some(dependent) = d.upgrade() else {
-                    return false;
-                };
-
-                // filter out things that are no longer dependent
-                if dependent.generation.get() > *gen {
-                    return false;
-                }
+        self.dependents.mark_dirty();
 
-                dependent.dirty.set(true);
+        self.value_mut()
+    }
 
-                mark_dirty(&dependent.dependents);
+    /// Like [`Rx::get_mut`], but the dependents only get invalidated once the batch is done.
+    #[track_caller]
+    pub fn get_mut_batched(&mut self, batch: &mut Batch) -> &mut T {
+        self.dependents.mark_dirty_batched(batch);
 
-                true
-            });
+        self.value_mut()
+    }
+
+    #[track_caller]
+    pub fn set(&mut self, value: T) {
+        *self.get_mut() = value;
+
+        #[cfg(feature = "futures")]
+        self.subscribers.publish(&self.value);
+    }
+
+    /// Yields the new value every time this gets changed. If it changes several times before the
+    /// stream gets polled, only the latest value is yielded. The stream ends when this is dropped.
+    ///
+    /// Changes made through [`Rx::get_mut`] only get yielded once the value is accessed again (or
+    /// this is dropped), because that's the first time what it was changed to is known. Usually
+    /// that happens soon, since the dependents that were invalidated read it.
+    #[cfg(feature = "futures")]
+    pub fn changes(&self) -> Changes<T> {
+        self.subscribers.flush(&self.value);
+        self.subscribers.subscribe()
+    }
+
+    /// Only invalidates the dependents if the new value differs from the current one. Returns
+    /// whether that was the case.
+    #[track_caller]
+    pub fn set_if_changed(&mut self, value: T) -> bool
+    where
+        T: PartialEq,
+    {
+        if self.value == value {
+            return false;
         }
 
-        mark_dirty(&self.dependents);
+        self.set(value);
 
-        &mut self.value
+        true
     }
 }
 
@@ -98,240 +294,1648 @@ impl<I: PartialEq, O> Default for RxFn<I, O> {
 // TODO: Add a test and comment that explains the reasoning for this.
 impl<I: PartialEq, O> Clone for RxFn<I, O> {
     fn clone(&self) -> Self {
-        Self::new()
+        RxFn::with_name(self.this.dependents.0.name.clone())
     }
 }
 
 impl<I: PartialEq, O> RxFn<I, O> {
     pub fn new() -> Self {
+        RxFn::with_name(None)
+    }
+
+    /// The name shows up in the debug output and the graph exported by [`debug::to_dot`].
+    pub fn new_named(name: impl Into<Cow<'static, str>>) -> Self {
+        RxFn::with_name(Some(name.into()))
+    }
+
+    fn with_name(name: Option<Cow<'static, str>>) -> Self {
         RxFn {
             last_input: None,
             result: None,
-            this: Rc::new(Dependent {
-                generation: Cell::new(0),
-                dirty: Cell::new(true),
-                dependents: RefCell::new(Vec::new()),
-            }),
+            this: Dependent::new(name),
         }
     }
 
-    pub fn call(&mut self, ctx: &RxCtx, params: I, mut closure: impl FnMut(&RxCtx, &I) -> O) -> &O {
-        let mut dependents = self.this.dependents.borrow_mut();
+    /// The [`Dependent`] this runs its closure in, for inspecting the dependency graph.
+    pub fn dependent(&self) -> &Rc<Dependent> {
+        &self.this
+    }
 
-        let mut push = true;
+    /// The result of the last call, without tracking or recomputing anything. This can be out of
+    /// date.
+    pub fn peek(&self) -> Option<&O> {
+        self.result.as_ref()
+    }
 
-        dependents.retain_mut(|(gen, d)| {
-            let Some(dependent) = d.upgrade() else {
-                // filter out dependents that no longer exist
-                return false;
-            };
+    /// Makes the next call recompute even if nothing it depends on has changed. Everything that
+    /// depends on this gets invalidated too.
+    pub fn invalidate(&mut self) {
+        mark_dependent_dirty(&self.this);
+    }
 
-            if Rc::ptr_eq(&dependent, ctx.dependent) {
-                *gen = ctx.dependent.generation.get();
-                push = false;
-            }
+    pub fn call(&mut self, ctx: &RxCtx, params: I, closure: impl FnMut(&RxCtx, &I) -> O) -> &O {
+        self.call_impl(ctx, params, closure, I::eq, |_, _| false)
+    }
 
-            true
-        });
+    /// Like [`RxFn::call`], but uses `eq` instead of [`PartialEq`] to decide whether the parameters
+    /// are the same as last time. This is useful for things like floats where small differences or
+    /// NaNs shouldn't cause a recomputation.
+    pub fn call_with_cmp(
+        &mut self,
+        ctx: &RxCtx,
+        params: I,
+        eq: impl FnOnce(&I, &I) -> bool,
+        closure: impl FnMut(&RxCtx, &I) -> O,
+    ) -> &O {
+        self.call_impl(ctx, params, closure, eq, |_, _| false)
+    }
 
-        if push {
-            dependents.push((ctx.dependent.generation.get(), Rc::downgrade(ctx.dependent)));
-        }
+    /// Like [`RxFn::call`], but if a recomputation produces a result that is equal to the previous
+    /// one, it doesn't count as a change.
+    ///
+    /// That only saves work for dependents that get checked after the recomputation: others that
+    /// call this later in the same pass and the toplevel [`Dependent`] whose dirty flag is looked
+    /// at afterwards. They don't rerun if they were only invalidated through this. The computation
+    /// whose closure calls this still reruns, because running it is the only way to get this to
+    /// recompute. In a chain of memos that call each other, all of them rerun and the cutoff only
+    /// happens above the outermost one. Use [`RcMemo`] for the memos in the middle of such a chain
+    /// to cut off at every level.
+    pub fn call_with_eq(
+        &mut self,
+        ctx: &RxCtx,
+        params: I,
+        closure: impl FnMut(&RxCtx, &I) -> O,
+    ) -> &O
+    where
+        O: PartialEq,
+    {
+        self.call_impl(ctx, params, closure, I::eq, O::eq)
+    }
+
+    /// Like [`RxFn::call`], but takes the parameters by reference and only stores the key that gets
+    /// extracted from them. Whether to recompute is decided by comparing that key.
+    pub fn call_keyed<P: ?Sized>(
+        &mut self,
+        ctx: &RxCtx,
+        params: &P,
+        key: impl FnOnce(&P) -> I,
+        mut closure: impl FnMut(&RxCtx, &P) -> O,
+    ) -> &O {
+        self.call_impl(
+            ctx,
+            key(params),
+            |ctx, _| closure(ctx, params),
+            I::eq,
+            |_, _| false,
+        )
+    }
+
+    fn call_impl(
+        &mut self,
+        ctx: &RxCtx,
+        params: I,
+        closure: impl FnOnce(&RxCtx, &I) -> O,
+        input_eq: impl FnOnce(&I, &I) -> bool,
+        output_eq: impl FnOnce(&O, &O) -> bool,
+    ) -> &O {
+        self.this.dependents.track(ctx);
+
+        self.update(params, closure, input_eq, output_eq)
+    }
+
+    /// Recomputes if necessary, without tracking anything.
+    fn update(
+        &mut self,
+        params: I,
+        closure: impl FnOnce(&RxCtx, &I) -> O,
+        input_eq: impl FnOnce(&I, &I) -> bool,
+        output_eq: impl FnOnce(&O, &O) -> bool,
+    ) -> &O {
+        refresh_pending(&self.this);
 
-        // Maybe != is not quite right here because we don't want trigger a re-run every time a NaN
-        // gets passed.
         // The unwrap works because the whole thing starts out dirty and after that there's always
         // something in the option.
-        if self.this.dirty.get() || self.last_input.as_ref().unwrap() != &params {
+        if self.this.dirty() || !input_eq(self.last_input.as_ref().unwrap(), &params) {
             let params: &I = self.last_input.insert(params);
-            self.this.dirty.set(false);
-            self.this.generation.set(self.this.generation.get() + 1);
+            self.this.start_run();
+
+            // If the closure panics this needs to stay dirty. Otherwise the next call would return
+            // the old result, which doesn't belong to the input that was already stored.
+            let guard = DirtyOnUnwind(&self.this);
+
+            #[cfg(feature = "stats")]
+            stats::record(&self.this.stats, true);
+
+            #[cfg(feature = "test-util")]
+            test_util::record_run(&self.this);
+
+            #[cfg(feature = "tracing")]
+            let span = tracing::debug_span!(
+                "recompute",
+                name = self.this.name(),
+                generation = self.this.generation.get(),
+            )
+            .entered();
+
+            #[cfg(feature = "profiling")]
+            let start = std::time::Instant::now();
+
+            let result = hazards::computing(|| {
+                implicit::enter(&self.this, || {
+                    closure(
+                        &RxCtx {
+                            dependent: &self.this,
+                        },
+                        params,
+                    )
+                })
+            });
 
-            let result = self.result.insert(closure(
-                &RxCtx {
-                    dependent: &self.this,
-                },
-                params,
-            ));
+            std::mem::forget(guard);
+
+            #[cfg(feature = "profiling")]
+            {
+                let elapsed = start.elapsed();
+                stats::record_time(&self.this.timing, elapsed);
+
+                #[cfg(feature = "tracing")]
+                tracing::debug!(?elapsed, "computed");
+            }
+
+            let changed = !self
+                .result
+                .as_ref()
+                .is_some_and(|old| output_eq(old, &result));
+
+            #[cfg(feature = "tracing")]
+            {
+                if !changed {
+                    tracing::debug!("result unchanged");
+                }
+
+                drop(span);
+            }
+
+            settle(&self.this, changed);
 
-            result
+            self.result.insert(result)
         } else {
+            #[cfg(feature = "stats")]
+            stats::record(&self.this.stats, false);
+
             self.result.as_ref().unwrap()
         }
     }
 }
 
-pub struct RxCtx<'a> {
-    dependent: &'a Rc<Dependent>,
+/// Whether [`RxFn::try_call`] keeps errors around like any other result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorPolicy {
+    /// Calling it again with the same input gives the same error until something it depends on
+    /// changes.
+    Cache,
+    /// Errors always get computed again on the next call.
+    Retry,
 }
 
-#[derive(Debug)]
-pub struct Dependent {
-    generation: Cell<u64>,
-    dirty: Cell<bool>,
-    dependents: RefCell<Vec<(u64, Weak<Dependent>)>>,
-}
+impl<I: PartialEq, T, E> RxFn<I, Result<T, E>> {
+    /// Like [`RxFn::call`], for closures that can fail.
+    pub fn try_call(
+        &mut self,
+        ctx: &RxCtx,
+        params: I,
+        policy: ErrorPolicy,
+        closure: impl FnMut(&RxCtx, &I) -> Result<T, E>,
+    ) -> Result<&T, &E> {
+        if policy == ErrorPolicy::Retry && matches!(self.result, Some(Err(_))) {
+            self.this.dirty.set(true);
+        }
 
-impl Dependent {
-    pub fn toplevel() -> Rc<Self> {
-        Rc::new(Dependent {
-            generation: Cell::new(0),
-            dirty: Cell::new(true),
-            dependents: RefCell::new(Vec::new()),
-        })
+        self.call(ctx, params, closure).as_ref()
     }
+}
 
-    pub fn ctx<'a>(self: &'a Rc<Self>) -> RxCtx<'a> {
-        RxCtx { dependent: self }
+impl<I: PartialEq, O> RxFn<I, Rc<O>> {
+    /// Like [`RxFn::call`], but hands out a shared handle to the result, so it can be kept around
+    /// (for example in a widget tree) without borrowing this.
+    pub fn call_rc(
+        &mut self,
+        ctx: &RxCtx,
+        params: I,
+        mut closure: impl FnMut(&RxCtx, &I) -> O,
+    ) -> Rc<O> {
+        self.call(ctx, params, |ctx, params| Rc::new(closure(ctx, params)))
+            .clone()
     }
+}
 
-    pub fn dirty(&self) -> bool {
-        self.dirty.get()
+struct DirtyOnUnwind<'a>(&'a Dependent);
+
+impl Drop for DirtyOnUnwind<'_> {
+    fn drop(&mut self) {
+        self.0.dirty.set(true);
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+pub struct RxCtx<'a> {
+    dependent: &'a Rc<Dependent>,
+}
 
-    #[test]
-    fn test() {
-        struct MyState {
-            something: Rx<f64>,
-            layout: RxFn<f64, f64>,
-        }
+impl RxCtx<'_> {
+    /// Registers a closure that gets called before the current computation runs again or when it
+    /// gets dropped. This can be used to tear down things like subscriptions.
+    pub fn on_cleanup(&self, cleanup: impl FnOnce() + 'static) {
+        self.dependent.cleanups.borrow_mut().push(Box::new(cleanup));
+    }
 
-        fn layout(ctx: &RxCtx, state: &mut MyState, width: f64) -> f64 {
-            *state.layout.call(ctx, width, |ctx, width| {
-                let height = state.something.get(ctx) / width;
+    /// A token that gets cancelled once the current computation gets invalidated, runs again or
+    /// gets dropped, because then its result is going to be thrown away. Long computations (or
+    /// work that was started from them, like a fetch) can check it to stop early.
+    pub fn cancellation_token(&self) -> CancelToken {
+        self.dependent
+            .cancellation
+            .borrow_mut()
+            .get_or_insert_with(CancelToken::default)
+            .clone()
+    }
+}
 
-                height
-            })
+/// The list of things that depend on a piece of reactive state. This is what [`Rx`] and the other
+/// reactive types are built on and it can be used to build custom ones.
+#[derive(Debug, Default)]
+pub struct Dependents(Rc<DependentsInner>);
+
+/// A dependent together with the generation it was in when it read the state.
+type Edge = (u64, Weak<Dependent>);
+
+// This is behind an Rc so that dependents can point back at their dependencies.
+#[derive(Debug, Default)]
+struct DependentsInner {
+    // Most state only has one or two dependents, so this avoids an allocation in the common case.
+    list: RefCell<SmallVec<[Edge; 2]>>,
+    /// The computation whose result this tracks, if there is one.
+    owner: Weak<Dependent>,
+    name: Option<Cow<'static, str>>,
+    /// Futures returned by [`Dependents::changed`] that are still waiting.
+    waiters: RefCell<Vec<Weak<changed::Waiter>>>,
+    /// How many dependents that were in the list got dropped since it was last compacted.
+    dropped: Cell<usize>,
+}
+
+impl DependentsInner {
+    fn compact(&self) {
+        // A dependent can get dropped while the list is being walked, which cleans it up anyway.
+        if let Ok(mut list) = self.list.try_borrow_mut() {
+            list.retain(|(gen, d)| d.upgrade().is_some_and(|d| d.generation.get() <= *gen));
+            list.shrink_to_fit();
+
+            self.dropped.set(0);
         }
 
-        let mut state = MyState {
-            something: Rx::new(128.),
-            layout: RxFn::new(),
-        };
+        if let Ok(mut waiters) = self.waiters.try_borrow_mut() {
+            waiters.retain(|waiter| waiter.strong_count() > 0);
+        }
+    }
 
-        let dependent = Dependent::toplevel();
-        let ctx = &dependent.ctx();
+    fn dependent_dropped(&self) {
+        let dropped = self.dropped.get() + 1;
+        self.dropped.set(dropped);
+
+        // Waiting until half of the list is dead keeps this amortized constant time per drop.
+        if self
+            .list
+            .try_borrow()
+            .is_ok_and(|list| dropped * 2 > list.len())
+        {
+            self.compact();
+        }
+    }
+}
 
-        assert_eq!(layout(ctx, &mut state, 2.), 64.);
+impl Dependents {
+    pub fn new() -> Self {
+        Dependents::default()
+    }
 
-        // let ctx = RxCtx {}; // TODO: figure out where that comes from or if it's needed at all
-        *state.something.get_mut() = 64.;
+    pub fn new_named(name: impl Into<Cow<'static, str>>) -> Self {
+        Dependents::with_name(Some(name.into()))
+    }
 
-        assert_eq!(layout(ctx, &mut state, 2.), 32.);
+    fn with_name(name: Option<Cow<'static, str>>) -> Self {
+        Dependents(Rc::new(DependentsInner {
+            list: RefCell::new(SmallVec::new()),
+            owner: Weak::new(),
+            name,
+            waiters: RefCell::new(Vec::new()),
+            dropped: Cell::new(0),
+        }))
     }
 
-    #[test]
-    fn test_last_input_storage() {
-        let times_called = Cell::new(0);
+    pub fn name(&self) -> Option<&str> {
+        self.0.name.as_deref()
+    }
 
-        let mut f = RxFn::new();
-        let mut something = |ctx, num: u32| -> bool {
-            *f.call(ctx, num, |_ctx, num| {
-                times_called.set(times_called.get() + 1);
-                num & 1 == 0
-            })
-        };
+    /// Registers whatever is currently being evaluated in `ctx` as a dependent. Call this when the
+    /// state is read.
+    pub fn track(&self, ctx: &RxCtx) {
+        let generation = ctx.dependent.generation.get();
 
-        let dependent = Dependent::toplevel();
-        let ctx = &dependent.ctx();
+        let mut dependents = self.0.list.borrow_mut();
 
-        assert!(!something(ctx, 1));
-        assert_eq!(times_called.get(), 1);
-        assert!(!something(ctx, 1));
-        assert_eq!(times_called.get(), 1);
-        assert!(something(ctx, 310));
-        assert_eq!(times_called.get(), 2);
-    }
+        let mut found = None;
 
-    #[test]
-    fn test_dependency_change() {
-        let mut a = Rx::new(true);
-        let mut b = Rx::new(2);
+        dependents.retain_mut(|(gen, d)| {
+            let Some(dependent) = d.upgrade() else {
+                // filter out dependents that no longer exist
+                return false;
+            };
 
-        let mut f = RxFn::new();
-        let mut something = |ctx, a: &mut Rx<bool>, b: &mut Rx<u32>| -> bool {
-            *f.call(ctx, (), |ctx, ()| *a.get(ctx) || *b.get(ctx) > 3)
-        };
+            if Rc::ptr_eq(&dependent, ctx.dependent) {
+                found = Some(*gen);
+                *gen = generation;
 
-        let dependent = Dependent::toplevel();
-        let ctx = &dependent.ctx();
+                return true;
+            }
 
-        assert!(something(ctx, &mut a, &mut b));
-        assert_eq!(a.dependents.borrow().len(), 1);
-        assert_eq!(b.dependents.borrow().len(), 0);
+            // filter out things that are no longer dependent
+            dependent.generation.get() <= *gen
+        });
 
-        *a.get_mut() = false;
+        if found.is_none() {
+            dependents.push((generation, Rc::downgrade(ctx.dependent)));
+        }
 
-        assert!(!something(ctx, &mut a, &mut b));
-        assert_eq!(a.dependents.borrow().len(), 1);
-        assert_eq!(b.dependents.borrow().len(), 1);
+        // only record the dependency once per run
+        if found != Some(generation) {
+            ctx.dependent
+                .dependencies
+                .borrow_mut()
+                .push(Rc::downgrade(&self.0));
+        }
+    }
 
-        *a.get_mut() = true;
+    /// Resolves the next time this gets marked as dirty. This doesn't need any integration with
+    /// the executor that runs the future.
+    pub fn changed(&self) -> Changed {
+        let changed = Changed::new();
 
-        assert!(something(ctx, &mut a, &mut b));
-        assert_eq!(a.dependents.borrow().len(), 1);
-        assert_eq!(b.dependents.borrow().len(), 1);
+        let mut waiters = self.0.waiters.borrow_mut();
 
-        *b.get_mut() = 513;
+        // filter out futures that were dropped before anything happened
+        waiters.retain(|waiter| waiter.strong_count() > 0);
+        waiters.push(Rc::downgrade(changed.waiter()));
+        drop(waiters);
 
-        // After b has been mutated it should become aware that f is no longer dependent on it.
-        assert_eq!(b.dependents.borrow().len(), 0);
+        changed
     }
 
-    #[test]
-    fn test_nested() {
-        struct Inner {
-            a: Rx<bool>,
-            layout: RxFn<f64, f64>,
+    fn wake_waiters(&self) {
+        for waiter in self.0.waiters.take() {
+            if let Some(waiter) = waiter.upgrade() {
+                waiter.wake();
+            }
         }
+    }
 
-        fn inner_layout(ctx: &RxCtx, state: &mut Inner, width: f64) -> f64 {
-            *state.layout.call(ctx, width, |ctx, width| {
-                if *state.a.get(ctx) && *width > 0. {
-                    20.
-                } else {
-                    30.
-                }
-            })
-        }
+    /// Invalidates all the dependents. Call this when the state is mutated.
+    ///
+    /// In debug builds this gets reported if it happens while a computation is running. See
+    /// [`hazards::set_write_policy`].
+    #[track_caller]
+    pub fn mark_dirty(&self) {
+        hazards::check_write();
 
-        struct MyState {
-            // not sure if this should be an Rx
-            inner: Inner,
-            something: Rx<f64>,
-            layout: RxFn<f64, f64>,
-        }
+        self.mark_dirty_unchecked();
+    }
 
-        fn layout(ctx: &RxCtx, state: &mut MyState, width: f64) -> f64 {
-            *state.layout.call(ctx, width, |ctx, width| {
-                let height = state.something.get(ctx) / width
-                    + inner_layout(ctx, &mut state.inner, width - 1.);
+    /// Like [`Dependents::mark_dirty`], for the places where it's fine to mutate while a
+    /// computation is running.
+    pub(crate) fn mark_dirty_unchecked(&self) {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(name = self.name(), "mark dirty");
 
-                height
-            })
-        }
+        self.wake_waiters();
 
-        let mut state = MyState {
-            inner: Inner {
-                a: Rx::new(true),
-                layout: RxFn::new(),
-            },
-            something: Rx::new(128.),
-            layout: RxFn::new(),
-        };
+        self.0.list.borrow_mut().retain(|(gen, d)| {
+            let Some(dependent) = d.upgrade() else {
+                return false;
+            };
 
-        let dependent = Dependent::toplevel();
-        let ctx = &dependent.ctx();
+            // filter out things that are no longer dependent
+            if dependent.generation.get() > *gen {
+                return false;
+            }
 
-        assert_eq!(layout(ctx, &mut state, 2.), 84.);
+            mark_dependent_dirty(&dependent);
 
-        *state.inner.a.get_mut() = false;
+            true
+        });
+    }
 
-        assert_eq!(layout(ctx, &mut state, 2.), 94.);
+    /// Like [`Dependents::mark_dirty`], but the dependents only get invalidated once the batch is
+    /// done.
+    #[track_caller]
+    pub fn mark_dirty_batched(&self, batch: &mut Batch) {
+        hazards::check_write();
+
+        batch.changed.push(Dependents(self.0.clone()));
+
+        self.0.list.borrow_mut().retain(|(gen, d)| {
+            let Some(dependent) = d.upgrade() else {
+                return false;
+            };
+
+            if dependent.generation.get() > *gen {
+                return false;
+            }
+
+            if batch.seen.insert(d.as_ptr()) {
+                batch.dirty.push(d.clone());
+            }
+
+            true
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.list.borrow().is_empty()
+    }
+
+    /// Removes dependents that no longer exist or no longer depend on this and frees the memory
+    /// they took up. This already happens whenever the state is read or mutated, and once half of
+    /// the dependents in the list have been dropped. So it's only needed for freeing the memory
+    /// right away, like before measuring how much is in use.
+    pub fn compact(&self) {
+        self.0.compact();
+    }
+
+    /// Everything that currently depends on this.
+    pub fn dependents(&self) -> Vec<Rc<Dependent>> {
+        current_dependents(&self.0)
+    }
+}
+
+fn current_dependents(dependents: &DependentsInner) -> Vec<Rc<Dependent>> {
+    dependents
+        .list
+        .borrow()
+        .iter()
+        .filter_map(|(gen, d)| d.upgrade().filter(|d| d.generation.get() <= *gen))
+        .collect()
+}
+
+/// Something a [`Dependent`] depends on, for inspecting the dependency graph.
+#[derive(Debug, Clone)]
+pub struct Dependency(Rc<DependentsInner>);
+
+impl PartialEq for Dependency {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for Dependency {}
+
+impl Dependency {
+    pub fn name(&self) -> Option<&str> {
+        self.0.name.as_deref()
+    }
+
+    /// The computation that produces this, or `None` for state like an [`Rx`].
+    pub fn computation(&self) -> Option<Rc<Dependent>> {
+        self.0.owner.upgrade()
+    }
+
+    /// Everything that currently depends on this.
+    pub fn dependents(&self) -> Vec<Rc<Dependent>> {
+        current_dependents(&self.0)
+    }
+}
+
+fn mark_dependent_dirty(dependent: &Rc<Dependent>) {
+    let was_dirty = dependent.dirty();
+
+    dependent.dirty.set(true);
+    dependent.cancel_run();
+    dependent.notify();
+
+    // If it was already dirty everything downstream already knows about it.
+    if !was_dirty {
+        mark_pending(dependent.clone());
+    }
+}
+
+/// Marks everything downstream of `source` as possibly dirty. Whether they actually have to
+/// recompute is decided once `source` has recomputed (see [`settle`]).
+fn mark_pending(source: Rc<Dependent>) {
+    // This uses a worklist instead of recursion so that long chains can't overflow the stack.
+    let mut worklist = vec![source];
+
+    while let Some(source) = worklist.pop() {
+        source.dependents.0.list.borrow_mut().retain(|(gen, d)| {
+            let Some(dependent) = d.upgrade() else {
+                return false;
+            };
+
+            if dependent.generation.get() > *gen {
+                return false;
+            }
+
+            let was_dirty = dependent.dirty();
+
+            let mut pending = dependent.pending.borrow_mut();
+
+            if !pending.iter().any(|p| p.as_ptr() == Rc::as_ptr(&source)) {
+                pending.push(Rc::downgrade(&source));
+                drop(pending);
+
+                dependent.notify();
+
+                if !was_dirty {
+                    worklist.push(dependent);
+                }
+            }
+
+            true
+        });
+    }
+}
+
+/// Brings the sources that `dependent` is pending on up to date if they can do that on their own
+/// (see [`RcMemo`]). Once they have settled, `dependent` is only still dirty if one of them
+/// actually changed or it is pending on something that can't be refreshed like that.
+fn refresh_pending(dependent: &Dependent) {
+    if dependent.dirty.get() {
+        return;
+    }
+
+    // The borrow has to end before refreshing, because that settles the pending state.
+    let sources: Vec<_> = dependent
+        .pending
+        .borrow()
+        .iter()
+        .filter_map(Weak::upgrade)
+        .collect();
+
+    for source in sources {
+        if let Some(refresh) = source.refresh.get() {
+            refresh();
+        }
+
+        if dependent.dirty.get() {
+            return;
+        }
+    }
+}
+
+/// Resolves the pending state that `source` caused in its dependents after it has recomputed. If
+/// the result didn't change dependents that aren't dirty for any other reason become clean again.
+fn settle(source: &Rc<Dependent>, changed: bool) {
+    let mut worklist = vec![(source.clone(), changed)];
+
+    while let Some((source, changed)) = worklist.pop() {
+        source.dependents.0.list.borrow_mut().retain(|(gen, d)| {
+            let Some(dependent) = d.upgrade() else {
+                return false;
+            };
+
+            if dependent.generation.get() > *gen {
+                return false;
+            }
+
+            let mut pending = dependent.pending.borrow_mut();
+            let len = pending.len();
+
+            pending.retain(|p| p.as_ptr() != Rc::as_ptr(&source));
+
+            if pending.len() < len {
+                if changed {
+                    dependent.dirty.set(true);
+                    dependent.cancel_run();
+                } else if !dependent.dirty.get() && pending.is_empty() {
+                    drop(pending);
+
+                    worklist.push((dependent, false));
+                }
+            }
+
+            true
+        });
+    }
+}
+
+/// Collects invalidations so that the dependency graph only has to be walked once for all of them.
+/// See [`batch`].
+#[derive(Debug)]
+pub struct Batch {
+    dirty: Vec<Weak<Dependent>>,
+    // The same as `dirty`, for checking whether something is already in there. The weak references
+    // in `dirty` keep the allocations alive, so the pointers can't get reused during the batch.
+    seen: HashSet<*const Dependent>,
+    /// The state that was mutated, whose waiters get woken once everything is invalidated.
+    changed: Vec<Dependents>,
+}
+
+// This commits in drop so that state that was already mutated when `f` panics still invalidates
+// its dependents.
+impl Drop for Batch {
+    fn drop(&mut self) {
+        for dependent in std::mem::take(&mut self.dirty) {
+            if let Some(dependent) = dependent.upgrade() {
+                mark_dependent_dirty(&dependent);
+            }
+        }
+
+        for dependents in std::mem::take(&mut self.changed) {
+            dependents.wake_waiters();
+        }
+    }
+}
+
+/// Runs `f` and defers the invalidations caused by [`Rx::get_mut_batched`] and
+/// [`Dependents::mark_dirty_batched`] inside of it until it returns (or panics).
+pub fn batch<R>(f: impl FnOnce(&mut Batch) -> R) -> R {
+    let mut batch = Batch {
+        dirty: Vec::new(),
+        seen: HashSet::new(),
+        changed: Vec::new(),
+    };
+
+    f(&mut batch)
+}
+
+pub struct Dependent {
+    generation: Cell<u64>,
+    dirty: Cell<bool>,
+    /// The dependencies this was invalidated through that haven't recomputed yet.
+    pending: RefCell<Vec<Weak<Dependent>>>,
+    /// What this depended on during its latest run.
+    dependencies: RefCell<Vec<Weak<DependentsInner>>>,
+    dependents: Dependents,
+    on_dirty: RefCell<Option<Box<dyn Fn()>>>,
+    // Set by computations that can recompute without being called, like RcMemo. Dependents that
+    // are pending on this use it to find out whether they actually have to rerun.
+    refresh: OnceCell<Box<dyn Fn()>>,
+    cleanups: RefCell<Vec<Box<dyn FnOnce()>>>,
+    // Handed out by RxCtx::cancellation_token during the current run.
+    cancellation: RefCell<Option<CancelToken>>,
+    #[cfg(feature = "stats")]
+    stats: Cell<stats::Stats>,
+    #[cfg(feature = "profiling")]
+    timing: Cell<stats::Timing>,
+}
+
+impl fmt::Debug for Dependent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Dependent")
+            .field("name", &self.name())
+            .field("generation", &self.generation)
+            .field("dirty", &self.dirty)
+            .field("pending", &self.pending)
+            .field("dependencies", &self.dependencies)
+            .field("dependents", &self.dependents)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Dependent {
+    fn new(name: Option<Cow<'static, str>>) -> Rc<Self> {
+        let dependent = Rc::new_cyclic(|this| Dependent {
+            generation: Cell::new(0),
+            dirty: Cell::new(true),
+            pending: RefCell::new(Vec::new()),
+            dependencies: RefCell::new(Vec::new()),
+            dependents: Dependents(Rc::new(DependentsInner {
+                list: RefCell::new(SmallVec::new()),
+                owner: this.clone(),
+                name,
+                waiters: RefCell::new(Vec::new()),
+                dropped: Cell::new(0),
+            })),
+            on_dirty: RefCell::new(None),
+            refresh: OnceCell::new(),
+            cleanups: RefCell::new(Vec::new()),
+            cancellation: RefCell::new(None),
+            #[cfg(feature = "stats")]
+            stats: Cell::new(stats::Stats::default()),
+            #[cfg(feature = "profiling")]
+            timing: Cell::new(stats::Timing::default()),
+        });
+
+        #[cfg(feature = "leaks")]
+        leaks::register(&dependent);
+
+        dependent
+    }
+
+    pub fn toplevel() -> Rc<Self> {
+        Dependent::new(None)
+    }
+
+    pub fn toplevel_named(name: impl Into<Cow<'static, str>>) -> Rc<Self> {
+        Dependent::new(Some(name.into()))
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.dependents.name()
+    }
+
+    /// Sets a callback that gets called every time state this read directly changes, even if it
+    /// was already dirty before, and when a computation it depends on gets invalidated for the
+    /// first time since it last ran. Later changes behind a computation that is still dirty don't
+    /// call it again, because they can't make this any more out of date than it already is. This
+    /// can be used to schedule a re-render instead of polling [`Dependent::dirty`].
+    ///
+    /// The callback gets called while the invalidation is still in progress, so it must not access
+    /// any reactive state.
+    pub fn on_dirty(&self, callback: impl Fn() + 'static) {
+        *self.on_dirty.borrow_mut() = Some(Box::new(callback));
+    }
+
+    fn notify(&self) {
+        if let Some(callback) = &*self.on_dirty.borrow() {
+            callback();
+        }
+    }
+
+    fn cancel_run(&self) {
+        if let Some(token) = self.cancellation.take() {
+            token.cancel();
+        }
+    }
+
+    fn run_cleanups(&self) {
+        let cleanups = std::mem::take(&mut *self.cleanups.borrow_mut());
+
+        for cleanup in cleanups {
+            cleanup();
+        }
+    }
+
+    pub fn ctx<'a>(self: &'a Rc<Self>) -> RxCtx<'a> {
+        RxCtx { dependent: self }
+    }
+
+    /// Forgets everything that was tracked during the previous run.
+    fn start_run(&self) {
+        self.dirty.set(false);
+        self.pending.borrow_mut().clear();
+        self.generation.set(self.generation.get() + 1);
+        self.dependencies.borrow_mut().clear();
+        self.cancel_run();
+        self.run_cleanups();
+    }
+
+    /// Runs one frame of an immediate mode GUI (or anything else that reads reactive state from
+    /// the top) in this. It's also the current context for [`implicit`] while `f` runs.
+    ///
+    /// What was tracked in the previous frame gets forgotten and this is clean when `f` starts, so
+    /// [`Dependent::dirty`] afterwards tells whether something changed during the frame and
+    /// another one is needed.
+    pub fn frame<R>(self: &Rc<Self>, f: impl FnOnce(&RxCtx) -> R) -> R {
+        self.start_run();
+
+        implicit::enter(self, || f(&self.ctx()))
+    }
+
+    pub fn dirty(&self) -> bool {
+        self.dirty.get() || !self.pending.borrow().is_empty()
+    }
+
+    /// Returns whether this is dirty and makes it clean, in one step.
+    ///
+    /// Unlike [`Dependent::frame`] this keeps what was tracked, so later mutations make this dirty
+    /// again. Call it before the pass that reads the state, not after it: anything that changes
+    /// while that pass runs (for example from a callback inside of it) then leaves this dirty for
+    /// the next call instead of getting lost.
+    pub fn take_dirty(&self) -> bool {
+        let dirty = self.dirty();
+
+        self.dirty.set(false);
+        self.pending.borrow_mut().clear();
+
+        dirty
+    }
+
+    /// What this depended on during its latest run.
+    pub fn dependencies(&self) -> Vec<Dependency> {
+        self.dependencies
+            .borrow()
+            .iter()
+            .filter_map(|d| d.upgrade().map(Dependency))
+            .collect()
+    }
+
+    /// Everything that currently depends on the result of this.
+    pub fn dependents(&self) -> Vec<Rc<Dependent>> {
+        self.dependents.dependents()
+    }
+
+    /// How often the computation behind this was rerun or served from its cache.
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> stats::Stats {
+        self.stats.get()
+    }
+
+    /// How long the computation behind this took to run.
+    #[cfg(feature = "profiling")]
+    pub fn timing(&self) -> stats::Timing {
+        self.timing.get()
+    }
+}
+
+impl Drop for Dependent {
+    fn drop(&mut self) {
+        self.cancel_run();
+        self.run_cleanups();
+
+        for dependency in self.dependencies.get_mut().drain(..) {
+            if let Some(dependency) = dependency.upgrade() {
+                dependency.dependent_dropped();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() {
+        struct MyState {
+            something: Rx<f64>,
+            layout: RxFn<f64, f64>,
+        }
+
+        fn layout(ctx: &RxCtx, state: &mut MyState, width: f64) -> f64 {
+            *state.layout.call(ctx, width, |ctx, width| {
+                let height = state.something.get(ctx) / width;
+
+                height
+            })
+        }
+
+        let mut state = MyState {
+            something: Rx::new(128.),
+            layout: RxFn::new(),
+        };
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(layout(ctx, &mut state, 2.), 64.);
+
+        // let ctx = RxCtx {}; // TODO: figure out where that comes from or if it's needed at all
+        *state.something.get_mut() = 64.;
+
+        assert_eq!(layout(ctx, &mut state, 2.), 32.);
+    }
+
+    #[test]
+    fn test_last_input_storage() {
+        let times_called = Cell::new(0);
+
+        let mut f = RxFn::new();
+        let mut something = |ctx, num: u32| -> bool {
+            *f.call(ctx, num, |_ctx, num| {
+                times_called.set(times_called.get() + 1);
+                num & 1 == 0
+            })
+        };
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert!(!something(ctx, 1));
+        assert_eq!(times_called.get(), 1);
+        assert!(!something(ctx, 1));
+        assert_eq!(times_called.get(), 1);
+        assert!(something(ctx, 310));
+        assert_eq!(times_called.get(), 2);
+    }
+
+    #[test]
+    fn test_dependency_change() {
+        let mut a = Rx::new(true);
+        let mut b = Rx::new(2);
+
+        let mut f = RxFn::new();
+        let mut something = |ctx, a: &mut Rx<bool>, b: &mut Rx<u32>| -> bool {
+            *f.call(ctx, (), |ctx, ()| *a.get(ctx) || *b.get(ctx) > 3)
+        };
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert!(something(ctx, &mut a, &mut b));
+        assert_eq!(a.dependents.0.list.borrow().len(), 1);
+        assert_eq!(b.dependents.0.list.borrow().len(), 0);
+
+        *a.get_mut() = false;
+
+        assert!(!something(ctx, &mut a, &mut b));
+        assert_eq!(a.dependents.0.list.borrow().len(), 1);
+        assert_eq!(b.dependents.0.list.borrow().len(), 1);
+
+        *a.get_mut() = true;
+
+        assert!(something(ctx, &mut a, &mut b));
+        assert_eq!(a.dependents.0.list.borrow().len(), 1);
+        assert_eq!(b.dependents.0.list.borrow().len(), 1);
+
+        *b.get_mut() = 513;
+
+        // After b has been mutated it should become aware that f is no longer dependent on it.
+        assert_eq!(b.dependents.0.list.borrow().len(), 0);
+    }
+
+    #[test]
+    fn test_nested() {
+        struct Inner {
+            a: Rx<bool>,
+            layout: RxFn<f64, f64>,
+        }
+
+        fn inner_layout(ctx: &RxCtx, state: &mut Inner, width: f64) -> f64 {
+            *state.layout.call(ctx, width, |ctx, width| {
+                if *state.a.get(ctx) && *width > 0. {
+                    20.
+                } else {
+                    30.
+                }
+            })
+        }
+
+        struct MyState {
+            // not sure if this should be an Rx
+            inner: Inner,
+            something: Rx<f64>,
+            layout: RxFn<f64, f64>,
+        }
+
+        fn layout(ctx: &RxCtx, state: &mut MyState, width: f64) -> f64 {
+            *state.layout.call(ctx, width, |ctx, width| {
+                let height = state.something.get(ctx) / width
+                    + inner_layout(ctx, &mut state.inner, width - 1.);
+
+                height
+            })
+        }
+
+        let mut state = MyState {
+            inner: Inner {
+                a: Rx::new(true),
+                layout: RxFn::new(),
+            },
+            something: Rx::new(128.),
+            layout: RxFn::new(),
+        };
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(layout(ctx, &mut state, 2.), 84.);
+
+        *state.inner.a.get_mut() = false;
+
+        assert_eq!(layout(ctx, &mut state, 2.), 94.);
+    }
+
+    #[test]
+    fn test_early_cutoff() {
+        struct State {
+            a: Rx<i32>,
+            positive: RxFn<(), bool>,
+            f: RxFn<(), bool>,
+            g: RxFn<(), bool>,
+        }
+
+        fn positive(ctx: &RxCtx, positive: &mut RxFn<(), bool>, a: &Rx<i32>) -> bool {
+            *positive.call_with_eq(ctx, (), |ctx, ()| *a.get(ctx) > 0)
+        }
+
+        let f_called = Cell::new(0);
+        let g_called = Cell::new(0);
+
+        let run = |ctx, state: &mut State| -> (bool, bool) {
+            let f = *state.f.call(ctx, (), |ctx, ()| {
+                f_called.set(f_called.get() + 1);
+                positive(ctx, &mut state.positive, &state.a)
+            });
+
+            let g = *state.g.call(ctx, (), |ctx, ()| {
+                g_called.set(g_called.get() + 1);
+                !positive(ctx, &mut state.positive, &state.a)
+            });
+
+            (f, g)
+        };
+
+        let mut state = State {
+            a: Rx::new(1),
+            positive: RxFn::new(),
+            f: RxFn::new(),
+            g: RxFn::new(),
+        };
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(run(ctx, &mut state), (true, false));
+        assert_eq!((f_called.get(), g_called.get()), (1, 1));
+
+        *state.a.get_mut() = 2;
+
+        // f has to recompute to find out that positive didn't change, but after that g knows that
+        // it's still up to date.
+        assert_eq!(run(ctx, &mut state), (true, false));
+        assert_eq!((f_called.get(), g_called.get()), (2, 1));
+
+        *state.a.get_mut() = -1;
+
+        assert_eq!(run(ctx, &mut state), (false, true));
+        assert_eq!((f_called.get(), g_called.get()), (3, 2));
+    }
+
+    #[test]
+    fn test_early_cutoff_chain() {
+        let mut a = Rx::new(1);
+        let mut f = RxFn::new();
+        let mut g = RxFn::new();
+
+        let f_called = Cell::new(0);
+        let g_called = Cell::new(0);
+
+        let dependent = Dependent::toplevel();
+
+        let mut run = |a: &Rx<i32>| {
+            let ctx = &dependent.ctx();
+
+            *g.call_with_eq(ctx, (), |ctx, ()| {
+                g_called.set(g_called.get() + 1);
+
+                *f.call_with_eq(ctx, (), |ctx, ()| {
+                    f_called.set(f_called.get() + 1);
+                    *a.get(ctx) > 0
+                })
+            })
+        };
+
+        assert!(run(&a));
+        dependent.take_dirty();
+
+        a.set(2);
+        assert!(dependent.dirty());
+
+        // g has to rerun to get f to recompute, but the toplevel isn't dirty afterwards
+        assert!(run(&a));
+        assert_eq!((f_called.get(), g_called.get()), (2, 2));
+        assert!(!dependent.dirty());
+    }
+
+    #[test]
+    fn test_set_if_changed() {
+        let times_called = Cell::new(0);
+
+        let mut a = Rx::new(1);
+
+        let mut f = RxFn::new();
+        let mut something = |ctx, a: &Rx<u32>| -> u32 {
+            *f.call(ctx, (), |ctx, ()| {
+                times_called.set(times_called.get() + 1);
+                *a.get(ctx) * 2
+            })
+        };
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(something(ctx, &a), 2);
+
+        assert!(!a.set_if_changed(1));
+        assert_eq!(something(ctx, &a), 2);
+        assert_eq!(times_called.get(), 1);
+
+        assert!(a.set_if_changed(2));
+        assert_eq!(something(ctx, &a), 4);
+        assert_eq!(times_called.get(), 2);
+
+        a.set(2);
+        assert_eq!(something(ctx, &a), 4);
+        assert_eq!(times_called.get(), 3);
+    }
+
+    #[test]
+    fn test_batch() {
+        let mut a = Rx::new(1);
+        let mut b = Rx::new(2);
+
+        let mut f = RxFn::new();
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(*f.call(ctx, (), |ctx, ()| a.get(ctx) + b.get(ctx)), 3);
+
+        let mut changed = a.changed();
+        let mut poll = || {
+            let cx = &mut std::task::Context::from_waker(std::task::Waker::noop());
+
+            std::future::Future::poll(std::pin::Pin::new(&mut changed), cx).is_ready()
+        };
+
+        batch(|batch| {
+            *a.get_mut_batched(batch) = 3;
+            *b.get_mut_batched(batch) = 4;
+
+            assert!(!f.this.dirty());
+            assert!(!poll());
+        });
+
+        assert!(f.this.dirty());
+        assert!(poll());
+        assert_eq!(*f.call(ctx, (), |ctx, ()| a.get(ctx) + b.get(ctx)), 7);
+    }
+
+    #[test]
+    fn test_batch_panic() {
+        let mut a = Rx::new(1);
+        let mut f = RxFn::new();
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(*f.call(ctx, (), |ctx, ()| *a.get(ctx)), 1);
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            batch(|batch| {
+                *a.get_mut_batched(batch) = 2;
+                panic!("oops");
+            })
+        }));
+
+        assert!(result.is_err());
+        assert!(f.this.dirty());
+        assert_eq!(*f.call(ctx, (), |ctx, ()| *a.get(ctx)), 2);
+    }
+
+    #[test]
+    fn test_diamond() {
+        struct State {
+            a: Rx<i32>,
+            b: RxFn<(), i32>,
+            c: RxFn<(), i32>,
+            d: RxFn<(), (i32, i32)>,
+        }
+
+        let b_called = Cell::new(0);
+        let c_called = Cell::new(0);
+
+        let b = |ctx: &RxCtx, b: &mut RxFn<(), i32>, a: &Rx<i32>| -> i32 {
+            *b.call(ctx, (), |ctx, ()| {
+                b_called.set(b_called.get() + 1);
+                a.get(ctx) + 1
+            })
+        };
+
+        let c = |ctx: &RxCtx, c: &mut RxFn<(), i32>, a: &Rx<i32>| -> i32 {
+            *c.call(ctx, (), |ctx, ()| {
+                c_called.set(c_called.get() + 1);
+                a.get(ctx) * 2
+            })
+        };
+
+        let d = |ctx: &RxCtx, state: &mut State| -> (i32, i32) {
+            *state.d.call(ctx, (), |ctx, ()| {
+                (
+                    b(ctx, &mut state.b, &state.a),
+                    c(ctx, &mut state.c, &state.a),
+                )
+            })
+        };
+
+        let mut state = State {
+            a: Rx::new(1),
+            b: RxFn::new(),
+            c: RxFn::new(),
+            d: RxFn::new(),
+        };
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(d(ctx, &mut state), (2, 2));
+
+        *state.a.get_mut() = 5;
+
+        // Nothing gets evaluated while the change propagates, so d can only ever see b and c after
+        // they have caught up with a, and each of them runs only once even though d and the
+        // toplevel both read them.
+        assert_eq!(d(ctx, &mut state), (6, 10));
+        assert_eq!(b(ctx, &mut state.b, &state.a), 6);
+        assert_eq!(c(ctx, &mut state.c, &state.a), 10);
+        assert_eq!((b_called.get(), c_called.get()), (2, 2));
+    }
+
+    #[test]
+    fn test_long_chain() {
+        let mut a = Rx::new(0);
+
+        let chain: Vec<_> = (0..100_000).map(|_| Dependent::toplevel()).collect();
+
+        a.get(&chain[0].ctx());
+
+        for pair in chain.windows(2) {
+            pair[0].dependents.track(&pair[1].ctx());
+        }
+
+        for dependent in &chain {
+            dependent.dirty.set(false);
+        }
+
+        *a.get_mut() = 1;
+
+        assert!(chain.iter().all(|dependent| dependent.dirty()));
+    }
+
+    #[test]
+    fn test_call_keyed() {
+        struct Big {
+            id: u32,
+            data: Vec<u8>,
+        }
+
+        let times_called = Cell::new(0);
+
+        let mut f = RxFn::new();
+        let mut something = |ctx, big: &Big| -> usize {
+            *f.call_keyed(
+                ctx,
+                big,
+                |big| big.id,
+                |_ctx, big| {
+                    times_called.set(times_called.get() + 1);
+                    big.data.len()
+                },
+            )
+        };
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        let mut big = Big {
+            id: 0,
+            data: vec![0; 16],
+        };
+
+        assert_eq!(something(ctx, &big), 16);
+
+        big.data.push(1);
+
+        // the key didn't change
+        assert_eq!(something(ctx, &big), 16);
+        assert_eq!(times_called.get(), 1);
+
+        big.id = 1;
+
+        assert_eq!(something(ctx, &big), 17);
+        assert_eq!(times_called.get(), 2);
+    }
+
+    #[test]
+    fn test_call_with_cmp() {
+        let times_called = Cell::new(0);
+
+        let mut f = RxFn::new();
+        let mut something = |ctx, width: f64| -> f64 {
+            *f.call_with_cmp(
+                ctx,
+                width,
+                |a, b| (a - b).abs() < 1e-9 || (a.is_nan() && b.is_nan()),
+                |_ctx, width| {
+                    times_called.set(times_called.get() + 1);
+                    width * 2.
+                },
+            )
+        };
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(something(ctx, 1.), 2.);
+        assert_eq!(something(ctx, 1. + 1e-15), 2.);
+        assert_eq!(times_called.get(), 1);
+
+        assert!(something(ctx, f64::NAN).is_nan());
+        assert!(something(ctx, f64::NAN).is_nan());
+        assert_eq!(times_called.get(), 2);
+    }
+
+    #[test]
+    fn test_on_dirty() {
+        let mut a = Rx::new(1);
+        let mut b = Rx::new(2);
+
+        let mut f = RxFn::new();
+
+        let times_notified = Rc::new(Cell::new(0));
+
+        let dependent = Dependent::toplevel();
+        dependent.on_dirty({
+            let times_notified = times_notified.clone();
+            move || times_notified.set(times_notified.get() + 1)
+        });
+
+        let ctx = &dependent.ctx();
+
+        a.get(ctx);
+        f.call(ctx, (), |ctx, ()| *b.get(ctx));
+
+        *a.get_mut() = 3;
+        assert_eq!(times_notified.get(), 1);
+
+        *b.get_mut() = 4;
+        assert_eq!(times_notified.get(), 2);
+
+        // f hasn't been called since, so the toplevel already knows that it's stale
+        *b.get_mut() = 5;
+        assert_eq!(times_notified.get(), 2);
+
+        // but state it read itself notifies it again
+        *a.get_mut() = 6;
+        assert_eq!(times_notified.get(), 3);
+    }
+
+    #[test]
+    fn test_frame() {
+        let mut a = Rx::new(1);
+        let b = Rx::new(2);
+
+        let dependent = Dependent::toplevel();
+
+        let sum = dependent.frame(|ctx| a.get(ctx) + b.get(ctx));
+
+        assert_eq!(sum, 3);
+        assert!(!dependent.dirty());
+
+        a.set(2);
+        assert!(dependent.dirty());
+
+        // only reads b this time
+        assert_eq!(dependent.frame(|ctx| *b.get(ctx)), 2);
+        assert!(!dependent.dirty());
+
+        a.set(3);
+        assert!(!dependent.dirty());
+        assert_eq!(dependent.dependencies().len(), 1);
+    }
+
+    #[test]
+    fn test_take_dirty() {
+        let mut a = Rx::new(1);
+
+        let dependent = Dependent::toplevel();
+
+        assert!(dependent.take_dirty());
+        assert_eq!(*a.get(&dependent.ctx()), 1);
+        assert!(!dependent.take_dirty());
+
+        a.set(2);
+
+        assert!(dependent.take_dirty());
+        assert!(!dependent.dirty());
+
+        // changes during the pass stay visible for the next call
+        assert_eq!(*a.get(&dependent.ctx()), 2);
+        a.set(3);
+
+        assert!(dependent.take_dirty());
+    }
+
+    #[test]
+    fn test_on_cleanup() {
+        let mut a = Rx::new(1);
+
+        let cleaned_up = Rc::new(Cell::new(0));
+
+        let mut f = RxFn::new();
+        let mut something = |ctx, a: &Rx<u32>| {
+            f.call(ctx, (), |ctx, ()| {
+                let cleaned_up = cleaned_up.clone();
+                ctx.on_cleanup(move || cleaned_up.set(cleaned_up.get() + 1));
+
+                *a.get(ctx)
+            });
+        };
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        something(ctx, &a);
+        something(ctx, &a);
+        assert_eq!(cleaned_up.get(), 0);
+
+        *a.get_mut() = 2;
+
+        something(ctx, &a);
+        assert_eq!(cleaned_up.get(), 1);
+
+        drop(f);
+        assert_eq!(cleaned_up.get(), 2);
+    }
+
+    #[test]
+    fn test_peek_and_invalidate() {
+        let times_called = Cell::new(0);
+
+        let mut f = RxFn::new();
+
+        assert_eq!(f.peek(), None);
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        let call = |f: &mut RxFn<(), u32>| {
+            *f.call(ctx, (), |_, ()| {
+                times_called.set(times_called.get() + 1);
+                times_called.get()
+            })
+        };
+
+        assert_eq!(call(&mut f), 1);
+        assert_eq!(call(&mut f), 1);
+        assert_eq!(f.peek(), Some(&1));
+
+        f.invalidate();
+
+        assert_eq!(f.peek(), Some(&1));
+        assert_eq!(call(&mut f), 2);
+    }
+
+    #[test]
+    fn test_panic_in_closure() {
+        let mut f = RxFn::new();
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(*f.call(ctx, 1, |_, x| x * 2), 2);
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            f.call(ctx, 2, |_, _| panic!());
+        }));
+
+        assert!(result.is_err());
+        assert!(f.dependent().dirty());
+        assert_eq!(*f.call(ctx, 2, |_, x| x * 2), 4);
+    }
+
+    #[test]
+    fn test_call_rc() {
+        let mut a = Rx::new(vec![1, 2]);
+        let mut f = RxFn::new();
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        let first = f.call_rc(ctx, (), |ctx, ()| a.get(ctx).clone());
+        let again = f.call_rc(ctx, (), |ctx, ()| a.get(ctx).clone());
+        assert!(Rc::ptr_eq(&first, &again));
+
+        a.get_mut().push(3);
+
+        let second = f.call_rc(ctx, (), |ctx, ()| a.get(ctx).clone());
+        assert_eq!((&*first, &*second), (&vec![1, 2], &vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn test_cancellation_token() {
+        let mut a = Rx::new(1);
+        let mut f = RxFn::new();
+        let mut tokens = Vec::new();
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        f.call(ctx, (), |ctx, ()| {
+            tokens.push(ctx.cancellation_token());
+            a.get(ctx);
+        });
+
+        assert!(!tokens[0].is_cancelled());
+
+        a.set(2);
+        assert!(tokens[0].is_cancelled());
+
+        f.call(ctx, (), |ctx, ()| {
+            tokens.push(ctx.cancellation_token());
+            a.get(ctx);
+        });
+
+        assert!(!tokens[1].is_cancelled());
+    }
+
+    #[test]
+    fn test_try_call() {
+        let times_called = Cell::new(0);
+
+        let mut f = RxFn::new();
+        let mut parse = |ctx, text: &str, policy| -> Result<u32, String> {
+            f.try_call(ctx, text.to_string(), policy, |_, text| {
+                times_called.set(times_called.get() + 1);
+                text.parse().map_err(|_| format!("not a number: {}", text))
+            })
+            .cloned()
+            .map_err(Clone::clone)
+        };
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(parse(ctx, "1", ErrorPolicy::Retry), Ok(1));
+        assert_eq!(parse(ctx, "1", ErrorPolicy::Retry), Ok(1));
+        assert_eq!(times_called.get(), 1);
+
+        assert!(parse(ctx, "x", ErrorPolicy::Cache).is_err());
+        assert!(parse(ctx, "x", ErrorPolicy::Cache).is_err());
+        assert_eq!(times_called.get(), 2);
+
+        assert!(parse(ctx, "x", ErrorPolicy::Retry).is_err());
+        assert_eq!(times_called.get(), 3);
+    }
+
+    #[test]
+    fn test_compact() {
+        let a = Rx::new(1);
+
+        let mut f = RxFn::new();
+        let mut g = RxFn::new();
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        f.call(ctx, (), |ctx, ()| *a.get(ctx));
+        g.call(ctx, (), |ctx, ()| *a.get(ctx));
+
+        drop(f);
+
+        assert_eq!(a.dependents.0.list.borrow().len(), 2);
+
+        a.compact();
+
+        assert_eq!(a.dependents.0.list.borrow().len(), 1);
+    }
+
+    #[test]
+    fn test_compact_on_drop() {
+        let a = Rx::new(1);
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        let mut fs: Vec<RxFn<(), i32>> = (0..4).map(|_| RxFn::new()).collect();
+
+        for f in &mut fs {
+            f.call(ctx, (), |ctx, ()| *a.get(ctx));
+        }
+
+        fs.truncate(2);
+        assert_eq!(a.dependents.0.list.borrow().len(), 4);
+
+        // now more than half of them are gone
+        fs.truncate(1);
+        assert_eq!(a.dependents.0.list.borrow().len(), 1);
+    }
+
+    #[test]
+    fn test_introspection() {
+        let a = Rx::new(true);
+        let b = Rx::new(2);
+
+        let mut f = RxFn::new();
+        let mut g = RxFn::new();
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        g.call(ctx, (), |ctx, ()| {
+            *f.call(ctx, (), |ctx, ()| *a.get(ctx) && *b.get(ctx) > 1)
+        });
+
+        let f_dependencies = f.dependent().dependencies();
+        assert_eq!(f_dependencies.len(), 2);
+        assert!(f_dependencies.iter().all(|d| d.computation().is_none()));
+
+        assert!(Rc::ptr_eq(&a.dependents()[0], f.dependent()));
+        assert!(Rc::ptr_eq(&b.dependents()[0], f.dependent()));
+
+        let g_dependencies = g.dependent().dependencies();
+        assert_eq!(g_dependencies.len(), 1);
+        assert!(Rc::ptr_eq(
+            &g_dependencies[0].computation().unwrap(),
+            f.dependent()
+        ));
+        assert!(Rc::ptr_eq(&f.dependent().dependents()[0], g.dependent()));
     }
 }
@@ -0,0 +1,102 @@
+use crate::{RxCtx, RxFn};
+
+/// Like an [`RxFn`], but remembers the results for the last `capacity` different inputs instead
+/// of only the latest one, so switching back and forth between a few inputs (two tabs, or two
+/// widths while a window gets resized) doesn't recompute every time.
+///
+/// Every remembered input tracks its own dependencies. Inputs are compared with [`PartialEq`], so
+/// this is meant for small capacities. For many keys see [`crate::RxFnMap`].
+#[derive(Debug)]
+pub struct LruRxFn<I: PartialEq, O> {
+    // most recently used first
+    entries: Vec<RxFn<I, O>>,
+    capacity: usize,
+}
+
+impl<I: PartialEq, O> LruRxFn<I, O> {
+    /// # Panics
+    ///
+    /// If `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "an LruRxFn needs room for at least one input");
+
+        LruRxFn {
+            entries: Vec::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Like [`RxFn::call`]. If the result for `params` isn't remembered, the least recently used
+    /// one gets replaced once there are `capacity` of them.
+    pub fn call(&mut self, ctx: &RxCtx, params: I, closure: impl FnMut(&RxCtx, &I) -> O) -> &O {
+        let found = self
+            .entries
+            .iter()
+            .position(|rx_fn| rx_fn.last_input.as_ref() == Some(&params));
+
+        match found {
+            Some(i) => self.entries[..=i].rotate_right(1),
+            None if self.entries.len() < self.capacity => self.entries.insert(0, RxFn::new()),
+            // The evicted entry gets reused, so that whatever depended on it still gets
+            // invalidated like with a plain RxFn that gets called with different parameters.
+            None => self.entries.rotate_right(1),
+        }
+
+        self.entries[0].call(ctx, params, closure)
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::{Dependent, Rx};
+
+    #[test]
+    fn test_lru_rx_fn() {
+        let mut text = Rx::new(String::from("abcdef"));
+        let times_called = Cell::new(0);
+
+        let mut lines = LruRxFn::new(2);
+        let mut wrap = |ctx, text: &Rx<String>, width: usize| -> usize {
+            *lines.call(ctx, width, |ctx, width| {
+                times_called.set(times_called.get() + 1);
+                text.get(ctx).len().div_ceil(*width)
+            })
+        };
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(wrap(ctx, &text, 2), 3);
+        assert_eq!(wrap(ctx, &text, 3), 2);
+        assert_eq!(wrap(ctx, &text, 2), 3);
+        assert_eq!(wrap(ctx, &text, 3), 2);
+        assert_eq!(times_called.get(), 2);
+
+        text.get_mut().push('g');
+
+        assert_eq!(wrap(ctx, &text, 2), 4);
+        assert_eq!(wrap(ctx, &text, 3), 3);
+        assert_eq!(times_called.get(), 4);
+
+        // evicts 2
+        assert_eq!(wrap(ctx, &text, 7), 1);
+        assert_eq!(wrap(ctx, &text, 3), 3);
+        assert_eq!(wrap(ctx, &text, 2), 4);
+        assert_eq!(times_called.get(), 6);
+    }
+}
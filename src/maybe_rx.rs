@@ -0,0 +1,70 @@
+use crate::{ReadRx, Rx, RxCtx};
+
+/// Either a fixed value or a signal, for struct fields (like the parameters of a widget) that
+/// should accept both without making the struct generic. See also [`crate::Source`].
+#[derive(Debug, Clone)]
+pub enum MaybeRx<'a, T> {
+    Const(T),
+    Signal(ReadRx<'a, T>),
+}
+
+impl<'a, T: Clone> MaybeRx<'a, T> {
+    /// Only tracks anything if this is a signal.
+    pub fn get(&self, ctx: &RxCtx) -> &T {
+        match self {
+            MaybeRx::Const(value) => value,
+            MaybeRx::Signal(rx) => rx.get(ctx),
+        }
+    }
+
+    pub fn is_const(&self) -> bool {
+        matches!(self, MaybeRx::Const(_))
+    }
+}
+
+impl<T> From<T> for MaybeRx<'_, T> {
+    fn from(value: T) -> Self {
+        MaybeRx::Const(value)
+    }
+}
+
+impl<'a, T> From<ReadRx<'a, T>> for MaybeRx<'a, T> {
+    fn from(rx: ReadRx<'a, T>) -> Self {
+        MaybeRx::Signal(rx)
+    }
+}
+
+impl<'a, T: Clone> From<&'a Rx<T>> for MaybeRx<'a, T> {
+    fn from(rx: &'a Rx<T>) -> Self {
+        MaybeRx::Signal(rx.reader())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Dependent;
+
+    #[test]
+    fn test_maybe_rx() {
+        struct Label<'a> {
+            text: MaybeRx<'a, &'static str>,
+        }
+
+        let text = Rx::new("a");
+
+        let fixed = Label { text: "b".into() };
+        let reactive = Label {
+            text: (&text).into(),
+        };
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(*fixed.text.get(ctx), "b");
+        assert!(text.dependents().is_empty());
+
+        assert_eq!(*reactive.text.get(ctx), "a");
+        assert_eq!(text.dependents().len(), 1);
+    }
+}
@@ -0,0 +1,241 @@
+use std::{
+    borrow::Cow,
+    cell::{Ref, RefCell},
+    fmt,
+    rc::Rc,
+};
+
+use crate::{Dependent, RxCtx, RxFn};
+
+/// An [`RxFn`] without parameters that owns its closure, so it doesn't have to be passed in every
+/// time the value is needed.
+pub struct Memo<'a, O> {
+    rx_fn: RxFn<(), O>,
+    closure: Box<dyn FnMut(&RxCtx) -> O + 'a>,
+}
+
+impl<O: fmt::Debug> fmt::Debug for Memo<'_, O> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Memo")
+            .field("rx_fn", &self.rx_fn)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, O> Memo<'a, O> {
+    pub fn new(closure: impl FnMut(&RxCtx) -> O + 'a) -> Self {
+        Memo {
+            rx_fn: RxFn::new(),
+            closure: Box::new(closure),
+        }
+    }
+
+    /// The name shows up in the debug output and the graph exported by [`crate::debug::to_dot`].
+    pub fn new_named(
+        name: impl Into<Cow<'static, str>>,
+        closure: impl FnMut(&RxCtx) -> O + 'a,
+    ) -> Self {
+        Memo {
+            rx_fn: RxFn::new_named(name),
+            closure: Box::new(closure),
+        }
+    }
+
+    /// Only runs the closure if something it depends on has changed since the last time.
+    pub fn get(&mut self, ctx: &RxCtx) -> &O {
+        let closure = &mut self.closure;
+
+        self.rx_fn.call(ctx, (), |ctx, ()| closure(ctx))
+    }
+}
+
+/// A memo that lives behind an [`Rc`] and owns its closure, so that it can recompute without being
+/// called. Clones refer to the same memo.
+///
+/// A computation that was only invalidated through memos like this brings them up to date before
+/// it decides whether to rerun, and only reruns if one of their results actually changed. Unlike
+/// with [`RxFn::call_with_eq`] this also works when the computation is a memo that calls this
+/// from its own closure, so in a chain of these an unchanged result stops the invalidation at
+/// every level.
+pub struct RcMemo<O>(Rc<MemoState<O>>);
+
+struct MemoState<O> {
+    // Kept outside of the RefCell so that we can still be tracked while we're being evaluated.
+    this: Rc<Dependent>,
+    rx_fn: RefCell<RxFn<(), O>>,
+    closure: RefCell<Closure<O>>,
+}
+
+type Closure<O> = Box<dyn FnMut(&RxCtx) -> O>;
+
+impl<O: fmt::Debug> fmt::Debug for RcMemo<O> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RcMemo")
+            .field("rx_fn", &self.0.rx_fn)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<O> Clone for RcMemo<O> {
+    fn clone(&self) -> Self {
+        RcMemo(self.0.clone())
+    }
+}
+
+impl<O: PartialEq + 'static> RcMemo<O> {
+    pub fn new(closure: impl FnMut(&RxCtx) -> O + 'static) -> Self {
+        RcMemo::from_rx_fn(RxFn::new(), Box::new(closure))
+    }
+
+    /// The name shows up in the debug output and the graph exported by [`crate::debug::to_dot`].
+    pub fn new_named(
+        name: impl Into<Cow<'static, str>>,
+        closure: impl FnMut(&RxCtx) -> O + 'static,
+    ) -> Self {
+        RcMemo::from_rx_fn(RxFn::new_named(name), Box::new(closure))
+    }
+
+    fn from_rx_fn(rx_fn: RxFn<(), O>, closure: Closure<O>) -> Self {
+        let state = Rc::new(MemoState {
+            this: rx_fn.this.clone(),
+            rx_fn: RefCell::new(rx_fn),
+            closure: RefCell::new(closure),
+        });
+
+        let weak = Rc::downgrade(&state);
+
+        // The dependent was just created, so nothing else could have set this.
+        let _ = state.this.refresh.set(Box::new(move || {
+            if let Some(state) = weak.upgrade() {
+                // If it is being evaluated right now, the dependents simply rerun.
+                if let (Ok(mut rx_fn), Ok(mut closure)) =
+                    (state.rx_fn.try_borrow_mut(), state.closure.try_borrow_mut())
+                {
+                    rx_fn.update((), |ctx, ()| closure(ctx), <()>::eq, O::eq);
+                }
+            }
+        }));
+
+        RcMemo(state)
+    }
+
+    /// Only runs the closure if something it depends on has changed since the last time.
+    ///
+    /// # Panics
+    ///
+    /// If this gets called from within its own closure.
+    pub fn get(&self, ctx: &RxCtx) -> Ref<'_, O> {
+        self.0.this.dependents.track(ctx);
+
+        let mut rx_fn = self.0.rx_fn.borrow_mut();
+        let mut closure = self.0.closure.borrow_mut();
+
+        rx_fn.update((), |ctx, ()| closure(ctx), <()>::eq, O::eq);
+        drop(rx_fn);
+
+        // The unwrap works because update always leaves a result behind.
+        Ref::map(self.0.rx_fn.borrow(), |rx_fn| rx_fn.peek().unwrap())
+    }
+
+    /// The [`Dependent`] this runs its closure in, for inspecting the dependency graph.
+    pub fn dependent(&self) -> &Rc<Dependent> {
+        &self.0.this
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
+    };
+
+    use super::*;
+    use crate::{RcRx, Rx};
+
+    #[test]
+    fn test_memo() {
+        let times_called = Rc::new(Cell::new(0));
+        let a = Rc::new(RefCell::new(Rx::new(2)));
+
+        let mut memo = Memo::new({
+            let times_called = times_called.clone();
+            let a = a.clone();
+
+            move |ctx| {
+                times_called.set(times_called.get() + 1);
+                *a.borrow().get(ctx) * 2
+            }
+        });
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(*memo.get(ctx), 4);
+        assert_eq!(*memo.get(ctx), 4);
+        assert_eq!(times_called.get(), 1);
+
+        a.borrow_mut().set(3);
+
+        assert_eq!(*memo.get(ctx), 6);
+        assert_eq!(times_called.get(), 2);
+    }
+
+    #[test]
+    fn test_rc_memo_cutoff() {
+        let a = RcRx::new(1i32);
+        let runs = Rc::new(RefCell::new(Vec::new()));
+
+        let sign = RcMemo::new({
+            let a = a.clone();
+            let runs = runs.clone();
+
+            move |ctx| {
+                runs.borrow_mut().push("sign");
+                a.get(ctx).signum()
+            }
+        });
+
+        let label = RcMemo::new({
+            let sign = sign.clone();
+            let runs = runs.clone();
+
+            move |ctx| {
+                runs.borrow_mut().push("label");
+                if *sign.get(ctx) > 0 {
+                    "positive"
+                } else {
+                    "not positive"
+                }
+            }
+        });
+
+        let mut text = RxFn::new();
+
+        let dependent = Dependent::toplevel();
+
+        let mut run = || {
+            let ctx = &dependent.ctx();
+
+            text.call(ctx, (), |ctx, ()| {
+                runs.borrow_mut().push("text");
+                format!("a is {}", label.get(ctx))
+            })
+            .clone()
+        };
+
+        assert_eq!(run(), "a is positive");
+        assert_eq!(runs.take(), ["text", "label", "sign"]);
+        dependent.take_dirty();
+
+        // only sign has to rerun to find out that nothing else has to
+        a.set(2);
+        assert_eq!(run(), "a is positive");
+        assert_eq!(runs.take(), ["sign"]);
+        assert!(!dependent.dirty());
+
+        a.set(-1);
+        assert_eq!(run(), "a is not positive");
+        assert_eq!(runs.take(), ["sign", "label", "text"]);
+    }
+}
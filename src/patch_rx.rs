@@ -0,0 +1,159 @@
+use std::fmt;
+
+use crate::{RxCtx, RxEvents};
+
+/// A value that can be changed through patches, so that readers can update what they derived
+/// from it incrementally instead of starting over. See [`PatchRx`].
+pub trait Diffable {
+    type Patch;
+
+    fn apply(&mut self, patch: &Self::Patch);
+}
+
+/// For big values like documents or meshes. Every change is a [`Diffable::Patch`], and a
+/// computation can ask for the patches that were applied since its previous run with
+/// [`PatchRx::patches`] instead of reading the whole value again.
+///
+/// Patches are kept until every computation that reads them has seen them, like with
+/// [`RxEvents`], so the readers have to be computations like an [`crate::RxFn`].
+pub struct PatchRx<T: Diffable> {
+    value: T,
+    patches: RxEvents<T::Patch>,
+}
+
+impl<T> fmt::Debug for PatchRx<T>
+where
+    T: Diffable + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PatchRx")
+            .field("value", &self.value)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Diffable> PatchRx<T> {
+    pub fn new(value: T) -> Self {
+        PatchRx {
+            value,
+            patches: RxEvents::new(),
+        }
+    }
+
+    /// The whole value.
+    pub fn get(&self, ctx: &RxCtx) -> &T {
+        self.patches.read(ctx);
+
+        &self.value
+    }
+
+    /// The patches that were applied since the previous run of the computation that is currently
+    /// being evaluated in `ctx`, or `None` if it reads this for the first time and therefore has
+    /// to start from [`PatchRx::get`].
+    pub fn patches(&self, ctx: &RxCtx) -> Option<&[T::Patch]> {
+        self.patches.read_since(ctx)
+    }
+
+    #[track_caller]
+    pub fn get_untracked(&self) -> &T {
+        crate::hazards::check_untracked_read();
+
+        &self.value
+    }
+
+    #[track_caller]
+    pub fn apply(&mut self, patch: T::Patch) {
+        self.value.apply(&patch);
+        self.patches.emit(patch);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    use crate::{Dependent, RxFn};
+
+    struct Lines(Vec<String>);
+
+    enum Edit {
+        Push(String),
+        Clear,
+    }
+
+    impl Diffable for Lines {
+        type Patch = Edit;
+
+        fn apply(&mut self, patch: &Edit) {
+            match patch {
+                Edit::Push(line) => self.0.push(line.clone()),
+                Edit::Clear => self.0.clear(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_patch_rx() {
+        let mut lines = PatchRx::new(Lines(vec!["a".into(), "bc".into()]));
+
+        let mut f = RxFn::new();
+        let mut full_reads = 0;
+        let mut total = 0;
+        let mut run = |ctx, lines: &PatchRx<Lines>| {
+            f.call(ctx, (), |ctx, ()| match lines.patches(ctx) {
+                Some(patches) => {
+                    for patch in patches {
+                        match patch {
+                            Edit::Push(line) => total += line.len(),
+                            Edit::Clear => total = 0,
+                        }
+                    }
+                }
+                None => {
+                    full_reads += 1;
+                    total = lines.get(ctx).0.iter().map(String::len).sum();
+                }
+            });
+        };
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        run(ctx, &lines);
+
+        lines.apply(Edit::Push("def".into()));
+        lines.apply(Edit::Push("g".into()));
+        run(ctx, &lines);
+        assert_eq!(lines.get_untracked().0.len(), 4);
+
+        lines.apply(Edit::Clear);
+        lines.apply(Edit::Push("hi".into()));
+        run(ctx, &lines);
+
+        assert_eq!(full_reads, 1);
+        assert_eq!(total, 2);
+    }
+
+    #[test]
+    fn test_apply_during_run() {
+        let lines = RefCell::new(PatchRx::new(Lines(Vec::new())));
+        let dependent = Dependent::toplevel();
+
+        dependent.frame(|ctx| lines.borrow().patches(ctx).is_none());
+        lines.borrow_mut().apply(Edit::Push("a".into()));
+
+        dependent.frame(|ctx| {
+            assert_eq!(lines.borrow().patches(ctx).map(<[_]>::len), Some(1));
+
+            lines.borrow_mut().apply(Edit::Clear);
+
+            // the patches of this run stay the same
+            assert_eq!(lines.borrow().patches(ctx).map(<[_]>::len), Some(1));
+        });
+
+        let next =
+            dependent.frame(|ctx| matches!(lines.borrow().patches(ctx), Some([Edit::Clear])));
+        assert!(next);
+    }
+}
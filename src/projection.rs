@@ -0,0 +1,86 @@
+use std::borrow::Cow;
+
+use crate::RxFn;
+
+/// Holds on to the part of a value that was projected out by [`crate::Rx::project`] (or the
+/// result of one of the [`crate::combinators`]), so that the dependents of the projection only
+/// get invalidated if that part changes.
+#[derive(Debug, Clone)]
+pub struct Projection<O>(pub(crate) RxFn<(), O>);
+
+impl<O> Default for Projection<O> {
+    fn default() -> Self {
+        Projection::new()
+    }
+}
+
+impl<O> Projection<O> {
+    pub fn new() -> Self {
+        Projection(RxFn::new())
+    }
+
+    /// The name shows up in the debug output and the graph exported by [`crate::debug::to_dot`].
+    pub fn new_named(name: impl Into<Cow<'static, str>>) -> Self {
+        Projection(RxFn::new_named(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::{Dependent, Rx};
+
+    #[test]
+    fn test_project() {
+        #[derive(Clone)]
+        struct State {
+            position: (i32, i32),
+            name: String,
+        }
+
+        let mut state = Rx::new(State {
+            position: (0, 0),
+            name: String::from("a"),
+        });
+
+        let mut position = Projection::new();
+
+        let f_called = Cell::new(0);
+        let g_called = Cell::new(0);
+
+        let mut f = RxFn::new();
+        let mut g = RxFn::new();
+
+        let mut run = |ctx, state: &Rx<State>| -> (i32, i32) {
+            let x = *f.call(ctx, (), |ctx, ()| {
+                f_called.set(f_called.get() + 1);
+                state.project(ctx, &mut position, |state| &state.position).0
+            });
+
+            let y = *g.call(ctx, (), |ctx, ()| {
+                g_called.set(g_called.get() + 1);
+                state.project(ctx, &mut position, |state| &state.position).1
+            });
+
+            (x, y)
+        };
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(run(ctx, &state), (0, 0));
+
+        state.get_mut().name.push('b');
+
+        // f has to rerun to find out that the position didn't change, but g doesn't
+        assert_eq!(run(ctx, &state), (0, 0));
+        assert_eq!((f_called.get(), g_called.get()), (2, 1));
+
+        state.get_mut().position = (1, 2);
+
+        assert_eq!(run(ctx, &state), (1, 2));
+        assert_eq!((f_called.get(), g_called.get()), (3, 2));
+    }
+}
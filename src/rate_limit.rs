@@ -0,0 +1,161 @@
+use std::time::Duration;
+
+use crate::{Rx, RxCtx};
+
+/// Only lets a value through once it hasn't changed for `delay`, for example to wait until the
+/// user has stopped typing before searching.
+///
+/// The time is passed in by the host (see [`crate::RxClock`] for what it's relative to), and
+/// [`Debounced::poll`] has to be called regularly (for example once per frame) to publish a value
+/// once the delay has passed.
+#[derive(Debug)]
+pub struct Debounced<T> {
+    value: Rx<T>,
+    pending: Option<(T, Duration)>,
+    delay: Duration,
+}
+
+impl<T: Clone> Debounced<T> {
+    pub fn new(value: T, delay: Duration) -> Self {
+        Debounced {
+            value: Rx::new(value),
+            pending: None,
+            delay,
+        }
+    }
+
+    /// Replaces the pending value and restarts the delay. Dependents don't see it yet.
+    pub fn set(&mut self, value: T, now: Duration) {
+        self.pending = Some((value, now));
+    }
+
+    /// Publishes the pending value if it's been at least `delay` since it was set. Returns
+    /// whether that happened.
+    pub fn poll(&mut self, now: Duration) -> bool {
+        match self.pending.take() {
+            Some((value, since)) if now.saturating_sub(since) >= self.delay => {
+                self.value.set(value);
+                true
+            }
+            pending => {
+                self.pending = pending;
+                false
+            }
+        }
+    }
+
+    /// Whether there's a value that hasn't been published yet.
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// The last published value.
+    pub fn get(&self, ctx: &RxCtx) -> &T {
+        self.value.get(ctx)
+    }
+}
+
+/// Lets a value through at most once per `interval`, for example for the mouse position. Unlike
+/// [`Debounced`] the first change gets through immediately, and a steady stream of changes still
+/// gets published regularly.
+///
+/// The time is passed in by the host (see [`crate::RxClock`] for what it's relative to), and
+/// [`Throttled::poll`] has to be called regularly (for example once per frame) so that the last
+/// value of a burst doesn't get stuck.
+#[derive(Debug)]
+pub struct Throttled<T> {
+    value: Rx<T>,
+    pending: Option<T>,
+    last_published: Option<Duration>,
+    interval: Duration,
+}
+
+impl<T: Clone> Throttled<T> {
+    pub fn new(value: T, interval: Duration) -> Self {
+        Throttled {
+            value: Rx::new(value),
+            pending: None,
+            last_published: None,
+            interval,
+        }
+    }
+
+    /// Publishes the value right away if the last one was published at least `interval` ago and
+    /// keeps it for [`Throttled::poll`] otherwise. Returns whether it was published.
+    pub fn set(&mut self, value: T, now: Duration) -> bool {
+        self.pending = Some(value);
+
+        self.poll(now)
+    }
+
+    /// Publishes the pending value if the last one was published at least `interval` ago.
+    /// Returns whether that happened.
+    pub fn poll(&mut self, now: Duration) -> bool {
+        let ready = self
+            .last_published
+            .is_none_or(|last| now.saturating_sub(last) >= self.interval);
+
+        if !ready {
+            return false;
+        }
+
+        let Some(value) = self.pending.take() else {
+            return false;
+        };
+
+        self.value.set(value);
+        self.last_published = Some(now);
+
+        true
+    }
+
+    /// Whether there's a value that hasn't been published yet.
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// The last published value.
+    pub fn get(&self, ctx: &RxCtx) -> &T {
+        self.value.get(ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Dependent;
+
+    #[test]
+    fn test_debounced_and_throttled() {
+        let ms = Duration::from_millis;
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        let mut debounced = Debounced::new("", Duration::from_millis(100));
+        assert_eq!(*debounced.get(ctx), "");
+        dependent.take_dirty();
+
+        debounced.set("a", ms(0));
+        debounced.set("ab", ms(50));
+        assert!(!debounced.poll(ms(120)));
+        assert!(!dependent.dirty());
+
+        assert!(debounced.poll(ms(150)));
+        assert!(dependent.dirty());
+        assert_eq!(*debounced.get(ctx), "ab");
+        assert!(!debounced.is_pending());
+
+        let mut throttled = Throttled::new(0, Duration::from_millis(100));
+
+        assert!(throttled.set(1, ms(0)));
+        assert!(!throttled.set(2, ms(30)));
+        assert!(!throttled.set(3, ms(60)));
+        assert_eq!(*throttled.get(ctx), 1);
+
+        assert!(!throttled.poll(ms(90)));
+        assert!(throttled.poll(ms(100)));
+        assert_eq!(*throttled.get(ctx), 3);
+        assert!(!throttled.poll(ms(300)));
+    }
+}
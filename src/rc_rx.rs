@@ -0,0 +1,124 @@
+use std::{borrow::Cow, cell::Ref, rc::Rc};
+
+use crate::{Dependent, RxCtx, RxRefCell, RxRefMut};
+
+/// A handle to reactive state that lives behind an [`Rc`]. Clones refer to the same value, so a
+/// handle can be moved into `'static` callbacks (like event handlers) while others are used in
+/// computations. Unlike with [`crate::rx_pair`] every handle can both read and write.
+#[derive(Debug, Default)]
+pub struct RcRx<T>(Rc<RxRefCell<T>>);
+
+impl<T> Clone for RcRx<T> {
+    fn clone(&self) -> Self {
+        RcRx(self.0.clone())
+    }
+}
+
+impl<T> RcRx<T> {
+    pub fn new(value: T) -> Self {
+        RcRx(Rc::new(RxRefCell::new(value)))
+    }
+
+    /// The name shows up in the debug output and the graph exported by [`crate::debug::to_dot`].
+    pub fn new_named(name: impl Into<Cow<'static, str>>, value: T) -> Self {
+        RcRx(Rc::new(RxRefCell::new_named(name, value)))
+    }
+
+    /// # Panics
+    ///
+    /// If the value is being updated at the same time.
+    pub fn get(&self, ctx: &RxCtx) -> Ref<'_, T> {
+        self.0.get(ctx)
+    }
+
+    /// See [`crate::Rx::get_untracked`].
+    #[track_caller]
+    pub fn get_untracked(&self) -> Ref<'_, T> {
+        self.0.get_untracked()
+    }
+
+    pub fn get_untracked_intentional(&self) -> Ref<'_, T> {
+        self.0.get_untracked_intentional()
+    }
+
+    /// See [`RxRefCell::borrow_mut`].
+    #[track_caller]
+    pub fn borrow_mut(&self) -> RxRefMut<'_, T> {
+        self.0.borrow_mut()
+    }
+
+    #[track_caller]
+    pub fn set(&self, value: T) {
+        self.0.set(value);
+    }
+
+    /// Only invalidates the dependents if the new value differs from the current one. Returns
+    /// whether that was the case.
+    #[track_caller]
+    pub fn set_if_changed(&self, value: T) -> bool
+    where
+        T: PartialEq,
+    {
+        if *self.0.get_untracked_intentional() == value {
+            return false;
+        }
+
+        self.set(value);
+
+        true
+    }
+
+    /// # Panics
+    ///
+    /// If the value is being read at the same time.
+    #[track_caller]
+    pub fn update<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(&mut self.borrow_mut())
+    }
+
+    /// Whether both handles refer to the same value.
+    pub fn ptr_eq(&self, other: &RcRx<T>) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+
+    /// Everything that currently depends on this.
+    pub fn dependents(&self) -> Vec<Rc<Dependent>> {
+        self.0.dependents()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RxFn;
+
+    #[test]
+    fn test_rc_rx() {
+        let count = RcRx::new(0);
+        let mut f = RxFn::new();
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        let on_click: Box<dyn Fn() + 'static> = {
+            let count = count.clone();
+
+            Box::new(move || count.update(|c| *c += 1))
+        };
+
+        let mut label = |ctx| {
+            f.call(ctx, (), |ctx, ()| format!("{}", count.get(ctx)))
+                .clone()
+        };
+
+        assert_eq!(label(ctx), "0");
+
+        on_click();
+        on_click();
+        assert_eq!(label(ctx), "2");
+
+        assert!(!count.set_if_changed(2));
+        assert!(count.ptr_eq(&count.clone()));
+        assert!(!count.ptr_eq(&RcRx::new(2)));
+    }
+}
@@ -0,0 +1,74 @@
+use std::rc::Rc;
+
+use crate::{Dependent, Rx, RxCtx};
+
+/// A view of an [`Rx`] that can only be read, for handing out state without allowing it to be
+/// mutated. Created by [`Rx::reader`].
+#[derive(Debug)]
+pub struct ReadRx<'a, T>(&'a Rx<T>);
+
+impl<T> Clone for ReadRx<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ReadRx<'_, T> {}
+
+impl<'a, T: Clone> From<&'a Rx<T>> for ReadRx<'a, T> {
+    fn from(rx: &'a Rx<T>) -> Self {
+        ReadRx(rx)
+    }
+}
+
+impl<T: Clone> Rx<T> {
+    pub fn reader(&self) -> ReadRx<'_, T> {
+        ReadRx(self)
+    }
+}
+
+impl<'a, T: Clone> ReadRx<'a, T> {
+    pub fn get(self, ctx: &RxCtx) -> &'a T {
+        self.0.get(ctx)
+    }
+
+    #[track_caller]
+    pub fn get_untracked(self) -> &'a T {
+        self.0.get_untracked()
+    }
+
+    /// See [`Rx::read`].
+    pub fn read(self) -> &'a T {
+        self.0.read()
+    }
+
+    /// Everything that currently depends on this.
+    pub fn dependents(self) -> Vec<Rc<Dependent>> {
+        self.0.dependents()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reader() {
+        struct Module {
+            state: Rx<u32>,
+        }
+
+        impl Module {
+            fn state(&self) -> ReadRx<'_, u32> {
+                self.state.reader()
+            }
+        }
+
+        let module = Module { state: Rx::new(3) };
+
+        let dependent = Dependent::toplevel();
+
+        assert_eq!(*module.state().get(&dependent.ctx()), 3);
+        assert_eq!(module.state().dependents().len(), 1);
+    }
+}
@@ -0,0 +1,130 @@
+use std::{fmt, sync::mpsc::Receiver};
+
+use crate::{Rx, RxCtx};
+
+/// Feeds messages from a channel into the reactive graph, for example the results of work that
+/// was done on other threads.
+///
+/// Nothing happens until [`RxReceiver::poll`] gets called, so this has to be polled regularly on
+/// the thread that owns the graph (for example once per frame).
+pub struct RxReceiver<T, S> {
+    receiver: Receiver<T>,
+    state: Rx<S>,
+    fold: Fold<T, S>,
+}
+
+type Fold<T, S> = Box<dyn FnMut(&mut S, T)>;
+
+impl<T, S: fmt::Debug> fmt::Debug for RxReceiver<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RxReceiver")
+            .field("state", &self.state)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> RxReceiver<T, Option<T>>
+where
+    T: Clone + 'static,
+{
+    /// Only keeps the latest message.
+    pub fn latest(receiver: Receiver<T>) -> Self {
+        RxReceiver::new(receiver, None, |latest, message| *latest = Some(message))
+    }
+}
+
+impl<T> RxReceiver<T, Vec<T>>
+where
+    T: Clone + 'static,
+{
+    /// Keeps all the messages. Use [`RxReceiver::get_mut`] to remove the ones that have been
+    /// handled.
+    pub fn collect(receiver: Receiver<T>) -> Self {
+        RxReceiver::new(receiver, Vec::new(), Vec::push)
+    }
+}
+
+impl<T, S: Clone> RxReceiver<T, S> {
+    /// Every received message gets folded into the state with `fold`.
+    pub fn new(receiver: Receiver<T>, initial: S, fold: impl FnMut(&mut S, T) + 'static) -> Self {
+        RxReceiver {
+            receiver,
+            state: Rx::new(initial),
+            fold: Box::new(fold),
+        }
+    }
+
+    /// Takes all the messages that are currently waiting in the channel. The dependents only get
+    /// invalidated once, and only if there was at least one. Returns whether that was the case.
+    pub fn poll(&mut self) -> bool {
+        let mut messages = self.receiver.try_iter().peekable();
+
+        if messages.peek().is_none() {
+            return false;
+        }
+
+        let state = self.state.get_mut();
+
+        for message in messages {
+            (self.fold)(state, message);
+        }
+
+        true
+    }
+
+    pub fn get(&self, ctx: &RxCtx) -> &S {
+        self.state.get(ctx)
+    }
+
+    #[track_caller]
+    pub fn get_untracked(&self) -> &S {
+        self.state.get_untracked()
+    }
+
+    pub fn get_mut(&mut self) -> &mut S {
+        self.state.get_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, sync::mpsc, thread};
+
+    use super::*;
+    use crate::{Dependent, RxFn};
+
+    #[test]
+    fn test_receiver() {
+        let (sender, receiver) = mpsc::channel();
+        let mut messages = RxReceiver::collect(receiver);
+
+        let times_called = Cell::new(0);
+
+        let mut f = RxFn::new();
+        let mut sum = |ctx, messages: &RxReceiver<u32, Vec<u32>>| -> u32 {
+            *f.call(ctx, (), |ctx, ()| {
+                times_called.set(times_called.get() + 1);
+                messages.get(ctx).iter().sum()
+            })
+        };
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(sum(ctx, &messages), 0);
+        assert!(!messages.poll());
+
+        thread::spawn(move || {
+            sender.send(1).unwrap();
+            sender.send(2).unwrap();
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(sum(ctx, &messages), 0);
+
+        assert!(messages.poll());
+        assert_eq!(sum(ctx, &messages), 3);
+        assert_eq!(times_called.get(), 2);
+    }
+}
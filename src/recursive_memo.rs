@@ -0,0 +1,126 @@
+use std::{cell::RefCell, collections::HashMap, fmt, hash::Hash, rc::Rc};
+
+use crate::{CycleError, RxCtx, RxFnCell};
+
+/// Memoizes a computation per key, where computing the value for one key can require the values
+/// for other keys (like cells in a spreadsheet referring to each other).
+///
+/// If the keys end up depending on themselves, the call that would close the cycle returns a
+/// [`CycleError`] and the computation can decide what to use instead. Everything stays tracked, so
+/// once the cycle is broken the affected keys get computed again.
+pub struct RecursiveMemo<'a, K, O> {
+    cells: RefCell<HashMap<K, Rc<RxFnCell<(), O>>>>,
+    compute: Compute<'a, K, O>,
+}
+
+type Compute<'a, K, O> = Box<dyn Fn(&RxCtx, &RecursiveMemo<'a, K, O>, &K) -> O + 'a>;
+
+impl<K: fmt::Debug, O: fmt::Debug> fmt::Debug for RecursiveMemo<'_, K, O> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RecursiveMemo")
+            .field("cells", &self.cells)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, K: Clone + Eq + Hash, O: Clone> RecursiveMemo<'a, K, O> {
+    /// `compute` gets the memo itself so it can ask for the values of other keys.
+    pub fn new(compute: impl Fn(&RxCtx, &RecursiveMemo<'a, K, O>, &K) -> O + 'a) -> Self {
+        RecursiveMemo {
+            cells: RefCell::new(HashMap::new()),
+            compute: Box::new(compute),
+        }
+    }
+
+    /// Returns a [`CycleError`] if this is called for a key while its value is being computed.
+    pub fn get(&self, ctx: &RxCtx, key: &K) -> Result<O, CycleError> {
+        // The cell is cloned out so that the map isn't borrowed while other keys get computed.
+        let cell = self
+            .cells
+            .borrow_mut()
+            .entry(key.clone())
+            .or_default()
+            .clone();
+
+        let value = cell.call(ctx, (), |ctx, ()| (self.compute)(ctx, self, key))?;
+
+        Ok(value.clone())
+    }
+
+    /// Forgets the value of a key that isn't needed anymore.
+    pub fn remove(&mut self, key: &K) {
+        self.cells.get_mut().remove(key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::{Dependent, Rx, RxCell};
+
+    #[test]
+    fn test_recursive_memo() {
+        // every cell is either a number or a reference to another cell
+        #[derive(Clone)]
+        enum Formula {
+            Number(i32),
+            Ref(usize),
+        }
+
+        let mut formulas = [Rx::new(Formula::Number(1)), Rx::new(Formula::Ref(0))];
+        let times_called = Cell::new(0);
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        let evaluate = |ctx: &RxCtx, formulas: &[Rx<Formula>; 2]| -> Vec<Option<i32>> {
+            let memo = RecursiveMemo::new(|ctx, memo, &i: &usize| {
+                times_called.set(times_called.get() + 1);
+
+                match *formulas[i].get(ctx) {
+                    Formula::Number(n) => Some(n),
+                    // a cycle evaluates to nothing
+                    Formula::Ref(j) => memo.get(ctx, &j).unwrap_or(None),
+                }
+            });
+
+            (0..2).map(|i| memo.get(ctx, &i).unwrap()).collect()
+        };
+
+        assert_eq!(evaluate(ctx, &formulas), [Some(1), Some(1)]);
+        assert_eq!(times_called.get(), 2);
+
+        formulas[0].set(Formula::Ref(1));
+
+        assert_eq!(evaluate(ctx, &formulas), [None, None]);
+    }
+
+    #[test]
+    fn test_recursive_memo_tracking() {
+        let base = RxCell::new(1);
+        let times_called = Cell::new(0);
+
+        let memo = RecursiveMemo::new(|ctx, memo, &n: &u32| {
+            times_called.set(times_called.get() + 1);
+
+            match n {
+                0 => base.get(ctx),
+                n => memo.get(ctx, &(n - 1)).unwrap() * 2,
+            }
+        });
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(memo.get(ctx, &3), Ok(8));
+        assert_eq!(memo.get(ctx, &3), Ok(8));
+        assert_eq!(times_called.get(), 4);
+
+        base.set(2);
+
+        assert_eq!(memo.get(ctx, &3), Ok(16));
+        assert_eq!(times_called.get(), 8);
+    }
+}
@@ -0,0 +1,191 @@
+use std::{
+    cell::{Cell, Ref, RefCell},
+    fmt,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+};
+
+use crate::{Dependents, Rx, RxCtx, RxFn};
+
+/// What a [`Resource`] currently has to show.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResourceState<T, E> {
+    Loading,
+    Ok(T),
+    Err(E),
+}
+
+type LocalFuture<T> = Pin<Box<dyn Future<Output = T>>>;
+type Fetch<'a, T, E> = Box<dyn FnMut(&RxCtx) -> LocalFuture<Result<T, E>> + 'a>;
+
+/// The result of an async computation, for things like loading data over the network.
+///
+/// Like a [`crate::Memo`] this owns its closure. The closure gets tracked while it creates the
+/// future (not while the future runs), and the resource fetches again whenever something it read
+/// changes. The futures get handed to the `spawn` function passed to [`Resource::new`], which is
+/// expected to run them on a local executor on the same thread.
+pub struct Resource<'a, T, E> {
+    shared: Rc<Shared<T, E>>,
+    fetcher: RxFn<(), ()>,
+    refetch: Rx<()>,
+    fetch: Fetch<'a, T, E>,
+    spawn: Box<dyn Fn(LocalFuture<()>) + 'a>,
+}
+
+struct Shared<T, E> {
+    state: RefCell<ResourceState<T, E>>,
+    dependents: Dependents,
+    // Used to throw away the results of fetches that got superseded while they were running.
+    current_fetch: Cell<u64>,
+}
+
+impl<T: fmt::Debug, E: fmt::Debug> fmt::Debug for Resource<'_, T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Resource")
+            .field("state", &self.shared.state)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, T: 'static, E: 'static> Resource<'a, T, E> {
+    pub fn new<F>(
+        spawn: impl Fn(Pin<Box<dyn Future<Output = ()>>>) + 'a,
+        mut fetch: impl FnMut(&RxCtx) -> F + 'a,
+    ) -> Self
+    where
+        F: Future<Output = Result<T, E>> + 'static,
+    {
+        Resource {
+            shared: Rc::new(Shared {
+                state: RefCell::new(ResourceState::Loading),
+                dependents: Dependents::new(),
+                current_fetch: Cell::new(0),
+            }),
+            fetcher: RxFn::new(),
+            refetch: Rx::new(()),
+            fetch: Box::new(move |ctx| Box::pin(fetch(ctx))),
+            spawn: Box::new(spawn),
+        }
+    }
+
+    /// Starts fetching if that hasn't happened yet or if something the fetch closure depends on
+    /// has changed.
+    pub fn state(&mut self, ctx: &RxCtx) -> Ref<'_, ResourceState<T, E>> {
+        let Resource {
+            shared,
+            fetcher,
+            refetch,
+            fetch,
+            spawn,
+        } = self;
+
+        fetcher.call_with_eq(ctx, (), |ctx, ()| {
+            refetch.get(ctx);
+
+            let future = fetch(ctx);
+
+            let id = shared.current_fetch.get() + 1;
+            shared.current_fetch.set(id);
+
+            *shared.state.borrow_mut() = ResourceState::Loading;
+            // This happens while the fetcher runs, but only affects readers of the state.
+            shared.dependents.mark_dirty_unchecked();
+
+            let shared = Rc::downgrade(shared);
+
+            spawn(Box::pin(async move {
+                let result = future.await;
+
+                // The resource might have been dropped or started another fetch in the meantime.
+                let Some(shared) = shared.upgrade() else {
+                    return;
+                };
+
+                if shared.current_fetch.get() == id {
+                    *shared.state.borrow_mut() = match result {
+                        Ok(value) => ResourceState::Ok(value),
+                        Err(error) => ResourceState::Err(error),
+                    };
+                    shared.dependents.mark_dirty();
+                }
+            }));
+        });
+
+        shared.dependents.track(ctx);
+
+        shared.state.borrow()
+    }
+
+    /// Fetches again the next time the state is needed, even if nothing changed.
+    pub fn refetch(&mut self) {
+        self.refetch.set(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::task::{Context, Waker};
+
+    use super::*;
+    use crate::Dependent;
+
+    #[test]
+    fn test_resource() {
+        let spawned: Rc<RefCell<Vec<LocalFuture<()>>>> = Rc::default();
+
+        let run = || {
+            for mut future in spawned.take() {
+                let _ = future
+                    .as_mut()
+                    .poll(&mut Context::from_waker(Waker::noop()));
+            }
+        };
+
+        let fetched = Cell::new(0);
+        let a = Rc::new(RefCell::new(Rx::new(1)));
+
+        let mut resource = Resource::new(
+            {
+                let spawned = spawned.clone();
+
+                move |future| spawned.borrow_mut().push(future)
+            },
+            |ctx| {
+                fetched.set(fetched.get() + 1);
+
+                let a = *a.borrow().get(ctx);
+
+                async move {
+                    match a {
+                        0 => Err("zero"),
+                        a => Ok(a * 2),
+                    }
+                }
+            },
+        );
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(*resource.state(ctx), ResourceState::Loading);
+        run();
+        assert_eq!(*resource.state(ctx), ResourceState::Ok(2));
+        assert_eq!(fetched.get(), 1);
+
+        a.borrow_mut().set(0);
+
+        assert_eq!(*resource.state(ctx), ResourceState::Loading);
+        run();
+        assert_eq!(*resource.state(ctx), ResourceState::Err("zero"));
+        assert_eq!(fetched.get(), 2);
+
+        resource.refetch();
+        a.borrow_mut().set(3);
+
+        assert_eq!(*resource.state(ctx), ResourceState::Loading);
+        run();
+        assert_eq!(*resource.state(ctx), ResourceState::Ok(6));
+        assert_eq!(fetched.get(), 3);
+    }
+}
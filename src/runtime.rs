@@ -0,0 +1,858 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::{BTreeMap, VecDeque},
+    error::Error,
+    fmt,
+    rc::{Rc, Weak},
+    time::Duration,
+};
+
+use crate::{Dependent, RxCtx};
+
+/// Runs effects: closures that read reactive state and do something with it outside of the graph,
+/// like writing to a file or updating a native widget.
+///
+/// Every effect runs in its own [`Dependent`]. Its [`Dependent::on_dirty`] callback puts it into
+/// the queue of the runtime whenever something it read changes, and [`Runtime::flush`] reruns
+/// everything in there. Clones refer to the same runtime.
+#[derive(Clone)]
+pub struct Runtime {
+    queue: Rc<Queue>,
+}
+
+struct Queue {
+    // Per lane sorted by the order the effects run in, which also means that every effect is only
+    // in here once.
+    effects: RefCell<BTreeMap<Lane, BTreeMap<Key, Weak<EffectState>>>>,
+    deferred: RefCell<VecDeque<Box<dyn FnOnce()>>>,
+    next_order: Cell<u64>,
+    flushing: Cell<bool>,
+    max_passes: usize,
+    strict: bool,
+}
+
+/// When an effect runs during a [`Runtime::flush`], relative to the others. Lower phases run
+/// first.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Phase(pub u8);
+
+impl Phase {
+    /// Where [`Runtime::effect`] puts effects.
+    pub const STATE: Phase = Phase(0);
+    pub const LAYOUT: Phase = Phase(1);
+    pub const RENDER: Phase = Phase(2);
+}
+
+type Key = (Phase, u64);
+
+/// Which flushes an effect runs in. [`Runtime::flush`] runs the effects of all lanes, while
+/// [`Runtime::flush_lane`] only runs those of one and leaves the others queued. This is for things
+/// like effects of off-screen content, which can wait until the UI is idle. Unlike phases, lanes
+/// don't change the order effects run in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Lane(pub u8);
+
+impl Lane {
+    /// Where effects start out.
+    pub const VISIBLE: Lane = Lane(0);
+    pub const OFFSCREEN: Lane = Lane(1);
+}
+
+/// Returned by [`Runtime::flush`].
+#[derive(Debug, Clone)]
+pub enum FlushError {
+    /// The effects kept invalidating each other for more passes than allowed.
+    TooManyPasses,
+    /// In strict mode: `writer` changed state that `reader` read, but `reader` already ran in this
+    /// flush.
+    WriteAfterRead {
+        writer: Rc<Dependent>,
+        reader: Rc<Dependent>,
+    },
+}
+
+impl fmt::Display for FlushError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlushError::TooManyPasses => {
+                f.write_str("effects kept invalidating effects that already ran")
+            }
+            FlushError::WriteAfterRead { writer, reader } => write!(
+                f,
+                "effect {} invalidated effect {}, which already ran",
+                writer.name().unwrap_or("?"),
+                reader.name().unwrap_or("?"),
+            ),
+        }
+    }
+}
+
+impl Error for FlushError {}
+
+impl fmt::Debug for Runtime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Runtime")
+            .field("queued", &self.queue.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Queue {
+    fn push(&self, effect: &Rc<EffectState>) {
+        if !effect.disposed.get() {
+            self.effects
+                .borrow_mut()
+                .entry(effect.lane.get())
+                .or_default()
+                .insert(effect.key(), Rc::downgrade(effect));
+        }
+    }
+
+    /// Takes the effect that runs next, out of `lane` or out of all of them.
+    fn pop(&self, lane: Option<Lane>) -> Option<(Key, Weak<EffectState>)> {
+        let mut effects = self.effects.borrow_mut();
+
+        let lane = match lane {
+            Some(lane) => lane,
+            None => {
+                effects
+                    .iter()
+                    .filter_map(|(lane, queued)| Some((*queued.first_key_value()?.0, *lane)))
+                    .min()?
+                    .1
+            }
+        };
+
+        effects.get_mut(&lane)?.pop_first()
+    }
+
+    fn len(&self) -> usize {
+        self.effects.borrow().values().map(BTreeMap::len).sum()
+    }
+}
+
+impl Default for Runtime {
+    fn default() -> Self {
+        Runtime::new()
+    }
+}
+
+impl Runtime {
+    /// Allows 100 passes per flush.
+    pub fn new() -> Self {
+        Runtime::with_max_passes(100)
+    }
+
+    /// A pass is a run over the queued effects in the order of their phases. Every time an effect
+    /// invalidates one that comes before it (or itself), the flush has to start a new pass.
+    pub fn with_max_passes(max_passes: usize) -> Self {
+        Runtime::with_options(max_passes, false)
+    }
+
+    /// A runtime for tests that need reproducible behavior. Every flush runs each effect at most
+    /// once, and an effect that changes state that an effect which already ran has read makes the
+    /// flush stop with [`FlushError::WriteAfterRead`] instead of starting another pass.
+    pub fn strict() -> Self {
+        Runtime::with_options(1, true)
+    }
+
+    fn with_options(max_passes: usize, strict: bool) -> Self {
+        Runtime {
+            queue: Rc::new(Queue {
+                effects: RefCell::new(BTreeMap::new()),
+                deferred: RefCell::new(VecDeque::new()),
+                next_order: Cell::new(0),
+                flushing: Cell::new(false),
+                max_passes,
+                strict,
+            }),
+        }
+    }
+
+    /// Registers `f` as an effect. It first runs on the next [`Runtime::flush`] and after that
+    /// every time something it read has changed. It stops once the returned [`Effect`] gets
+    /// dropped.
+    pub fn effect(&self, f: impl FnMut(&RxCtx) + 'static) -> Effect {
+        self.effect_in(Phase::STATE, f)
+    }
+
+    /// Like [`Runtime::effect`], but the effect runs in `phase`.
+    pub fn effect_in(&self, phase: Phase, mut f: impl FnMut(&RxCtx) + 'static) -> Effect {
+        self.register(
+            phase,
+            Box::new(move |ctx| {
+                f(ctx);
+
+                true
+            }),
+        )
+    }
+
+    /// Registers `f` to run once on the next [`Runtime::flush`]. After that the effect unsubscribes
+    /// from everything it read.
+    pub fn once(&self, f: impl FnOnce(&RxCtx) + 'static) -> Effect {
+        let mut f = Some(f);
+
+        self.register(
+            Phase::STATE,
+            Box::new(move |ctx| {
+                // It gets disposed after the first run, so this can't be called again.
+                (f.take().unwrap())(ctx);
+
+                false
+            }),
+        )
+    }
+
+    /// Like [`Runtime::effect`], but the effect disposes itself as soon as `cond` returns `false`.
+    /// `f` only runs while it returns `true`.
+    pub fn while_true(
+        &self,
+        mut cond: impl FnMut(&RxCtx) -> bool + 'static,
+        mut f: impl FnMut(&RxCtx) + 'static,
+    ) -> Effect {
+        self.register(
+            Phase::STATE,
+            Box::new(move |ctx| {
+                if !cond(ctx) {
+                    return false;
+                }
+
+                f(ctx);
+
+                true
+            }),
+        )
+    }
+
+    fn register(&self, phase: Phase, run: Run) -> Effect {
+        let order = self.queue.next_order.get();
+        self.queue.next_order.set(order + 1);
+
+        let state = Rc::new(EffectState {
+            phase,
+            order,
+            lane: Cell::new(Lane::VISIBLE),
+            queue: Rc::downgrade(&self.queue),
+            dependent: Dependent::new(None),
+            run: RefCell::new(run),
+            disposed: Cell::new(false),
+        });
+
+        let queue = Rc::downgrade(&self.queue);
+        let effect = Rc::downgrade(&state);
+
+        // This only touches the queue, which is allowed during an invalidation.
+        state.dependent.on_dirty(move || {
+            if let (Some(queue), Some(effect)) = (queue.upgrade(), effect.upgrade()) {
+                queue.push(&effect);
+            }
+        });
+
+        self.queue.push(&state);
+
+        Effect(state)
+    }
+
+    /// Like [`Runtime::effect`], but split into a part that reads the state and one that does
+    /// something with it. `deps` runs every time something it read changes, but `run` only runs
+    /// when the key it returned is different from the last one. This is for side effects like
+    /// writing files, where running again for a change that doesn't matter would be harmful.
+    pub fn effect_with_deps<K: PartialEq + 'static>(
+        &self,
+        mut deps: impl FnMut(&RxCtx) -> K + 'static,
+        mut run: impl FnMut(&K) + 'static,
+    ) -> Effect {
+        let mut last = None;
+
+        self.effect(move |ctx| {
+            let key = deps(ctx);
+
+            if last.as_ref() != Some(&key) {
+                run(&key);
+                last = Some(key);
+            }
+        })
+    }
+
+    /// Queues `f` to run once the effect that is currently running has finished, before any other
+    /// effect runs. Outside of a flush it runs at the start of the next one. Deferred closures run
+    /// in the order they were deferred in.
+    ///
+    /// Effects can use this to change state they read themselves (or that effects that already
+    /// ran in this flush read). Those effects then simply get queued again, instead of the change
+    /// getting lost or invalidating a run that is still in progress.
+    pub fn defer(&self, f: impl FnOnce() + 'static) {
+        self.queue.deferred.borrow_mut().push_back(Box::new(f));
+    }
+
+    /// Whether there are effects or deferred closures waiting for the next flush.
+    pub fn is_idle(&self) -> bool {
+        self.queue.len() == 0 && self.queue.deferred.borrow().is_empty()
+    }
+
+    fn run_deferred(&self) {
+        // The borrow has to end before the closure runs, because it might defer something else.
+        while let Some(f) = self.queue.deferred.borrow_mut().pop_front() {
+            f();
+        }
+    }
+
+    /// Reruns the queued effects until none are left, ordered by their phases and then by when
+    /// they were registered. That order only depends on the effects and not on what invalidated
+    /// them, and the memos they call get evaluated in the order they are called. An effect that changes state that other effects read makes them run
+    /// again during the same flush, even if they are in an earlier phase. See also
+    /// [`Runtime::defer`].
+    ///
+    /// If that takes more passes than allowed (see [`Runtime::with_max_passes`]), this stops and
+    /// returns an error. The effects that didn't get to run stay queued.
+    ///
+    /// Calling this from inside an effect does nothing, because the outer flush is going to get
+    /// to everything anyway.
+    pub fn flush(&self) -> Result<(), FlushError> {
+        self.flush_until(None, || false)
+    }
+
+    /// Like [`Runtime::flush`], but only runs the effects in `lane` (see [`Effect::set_lane`]).
+    /// The effects of other lanes stay queued, including the ones that get invalidated during
+    /// this flush. Memos that only those effects call don't get evaluated either, since memos only
+    /// run when something calls them.
+    pub fn flush_lane(&self, lane: Lane) -> Result<(), FlushError> {
+        self.flush_until(Some(lane), || false)
+    }
+
+    /// Like [`Runtime::flush`], but stops starting new effects once `budget` has passed, so that a
+    /// big invalidation can be spread across several frames. The effects that didn't get to run
+    /// stay queued for the next call. Returns how many that are (some of those might turn out not
+    /// to need to run).
+    ///
+    /// `now` is the current time, relative to whatever the host likes (see [`crate::RxClock`]).
+    pub fn flush_with_budget(
+        &self,
+        budget: Duration,
+        mut now: impl FnMut() -> Duration,
+    ) -> Result<usize, FlushError> {
+        let start = now();
+
+        self.flush_until(None, || now().saturating_sub(start) >= budget)?;
+
+        Ok(self.queue.len())
+    }
+
+    fn flush_until(
+        &self,
+        lane: Option<Lane>,
+        mut out_of_time: impl FnMut() -> bool,
+    ) -> Result<(), FlushError> {
+        if self.queue.flushing.replace(true) {
+            return Ok(());
+        }
+
+        let _guard = Flushing(&self.queue);
+
+        self.run_deferred();
+
+        let mut passes = 1;
+        let mut last = None;
+
+        loop {
+            let Some((key, effect)) = self.queue.pop(lane) else {
+                break;
+            };
+
+            let Some(effect) = effect.upgrade() else {
+                continue;
+            };
+
+            // It might have been queued through something that turned out not to change, or got
+            // disposed in the meantime.
+            if !effect.dependent.dirty() || effect.disposed.get() {
+                continue;
+            }
+
+            if last.is_some_and(|last| key <= last) {
+                passes += 1;
+
+                if passes > self.queue.max_passes {
+                    self.queue.push(&effect);
+
+                    return Err(FlushError::TooManyPasses);
+                }
+            }
+
+            last = Some(key);
+
+            effect.run();
+            self.run_deferred();
+
+            if self.queue.strict {
+                if let Some(reader) = self.invalidated_up_to(key) {
+                    return Err(FlushError::WriteAfterRead {
+                        writer: effect.dependent.clone(),
+                        reader,
+                    });
+                }
+            }
+
+            if out_of_time() {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Runtime {
+    /// The first queued effect that comes before the one with `key` (or is that one) and actually
+    /// has to run again.
+    fn invalidated_up_to(&self, key: Key) -> Option<Rc<Dependent>> {
+        self.queue
+            .effects
+            .borrow()
+            .values()
+            .flat_map(|queued| queued.range(..=key))
+            .filter_map(|(_, effect)| effect.upgrade())
+            .find(|effect| effect.dependent.dirty() && !effect.disposed.get())
+            .map(|effect| effect.dependent.clone())
+    }
+}
+
+// This resets the flag in drop so that a panicking effect doesn't block all later flushes.
+struct Flushing<'a>(&'a Queue);
+
+impl Drop for Flushing<'_> {
+    fn drop(&mut self) {
+        self.0.flushing.set(false);
+    }
+}
+
+struct EffectState {
+    phase: Phase,
+    order: u64,
+    lane: Cell<Lane>,
+    queue: Weak<Queue>,
+    dependent: Rc<Dependent>,
+    run: RefCell<Run>,
+    disposed: Cell<bool>,
+}
+
+/// Returns whether the effect should keep running.
+type Run = Box<dyn FnMut(&RxCtx) -> bool>;
+
+impl EffectState {
+    fn key(&self) -> Key {
+        (self.phase, self.order)
+    }
+
+    fn run(&self) {
+        if !self.dependent.frame(|ctx| (self.run.borrow_mut())(ctx)) {
+            self.dispose();
+        }
+    }
+
+    fn dispose(&self) {
+        self.disposed.set(true);
+
+        // A run that doesn't read anything drops the subscriptions of the previous one and runs its
+        // cleanups.
+        self.dependent.frame(|_| {});
+    }
+}
+
+/// An effect registered with [`Runtime::effect`].
+pub struct Effect(Rc<EffectState>);
+
+impl fmt::Debug for Effect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Effect")
+            .field("dependent", &self.0.dependent)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Effect {
+    /// The [`Dependent`] the effect runs in, for inspecting the dependency graph.
+    pub fn dependent(&self) -> &Rc<Dependent> {
+        &self.0.dependent
+    }
+
+    pub fn lane(&self) -> Lane {
+        self.0.lane.get()
+    }
+
+    /// Moves the effect to another lane. If it is queued, it stays queued in the new one.
+    pub fn set_lane(&self, lane: Lane) {
+        let old = self.0.lane.replace(lane);
+
+        let Some(queue) = self.0.queue.upgrade() else {
+            return;
+        };
+
+        let mut effects = queue.effects.borrow_mut();
+
+        if let Some(effect) = effects
+            .get_mut(&old)
+            .and_then(|queued| queued.remove(&self.0.key()))
+        {
+            effects
+                .entry(lane)
+                .or_default()
+                .insert(self.0.key(), effect);
+        }
+    }
+
+    /// Stops the effect without dropping it.
+    pub fn dispose(&self) {
+        self.0.dispose();
+    }
+
+    /// Whether the effect has stopped, either through [`Effect::dispose`] or on its own like with
+    /// [`Runtime::once`].
+    pub fn is_disposed(&self) -> bool {
+        self.0.disposed.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RcRx;
+
+    #[test]
+    fn test_runtime() {
+        let runtime = Runtime::new();
+
+        let count = RcRx::new(0);
+        let doubled = RcRx::new(0);
+        let log = Rc::new(RefCell::new(Vec::new()));
+
+        // registered first, but it reads what the second one writes
+        let _log = runtime.effect({
+            let doubled = doubled.clone();
+            let log = log.clone();
+
+            move |ctx| log.borrow_mut().push(*doubled.get(ctx))
+        });
+
+        let _double = runtime.effect({
+            let count = count.clone();
+            let doubled = doubled.clone();
+
+            move |ctx| {
+                let value = *count.get(ctx) * 2;
+                doubled.set_if_changed(value);
+            }
+        });
+
+        assert!(!runtime.is_idle());
+        runtime.flush().unwrap();
+        assert_eq!(*log.borrow(), [0]);
+
+        count.set(1);
+        count.set(2);
+        runtime.flush().unwrap();
+        assert_eq!(*log.borrow(), [0, 4]);
+
+        runtime.flush().unwrap();
+        assert!(runtime.is_idle());
+        assert_eq!(*log.borrow(), [0, 4]);
+
+        drop(_log);
+        count.set(3);
+        runtime.flush().unwrap();
+        assert_eq!(*log.borrow(), [0, 4]);
+        assert_eq!(*doubled.get_untracked_intentional(), 6);
+    }
+
+    #[test]
+    fn test_effect_with_deps() {
+        let runtime = Runtime::new();
+
+        let path = RcRx::new("a.txt");
+        let writes = Rc::new(Cell::new(0));
+
+        let _effect = runtime.effect_with_deps(
+            {
+                let path = path.clone();
+
+                move |ctx| path.get(ctx).len()
+            },
+            {
+                let writes = writes.clone();
+
+                move |_| writes.set(writes.get() + 1)
+            },
+        );
+
+        runtime.flush().unwrap();
+        assert_eq!(writes.get(), 1);
+
+        // the key stays the same
+        path.set("b.txt");
+        runtime.flush().unwrap();
+        assert_eq!(writes.get(), 1);
+
+        path.set("cd.txt");
+        runtime.flush().unwrap();
+        assert_eq!(writes.get(), 2);
+    }
+
+    #[test]
+    fn test_once_and_while_true() {
+        let runtime = Runtime::new();
+
+        let visible = RcRx::new(false);
+        let log = Rc::new(RefCell::new(Vec::new()));
+
+        let once = runtime.once({
+            let visible = visible.clone();
+            let log = log.clone();
+
+            move |ctx| log.borrow_mut().push(("once", *visible.get(ctx)))
+        });
+
+        let while_hidden = runtime.while_true(
+            {
+                let visible = visible.clone();
+
+                move |ctx| !*visible.get(ctx)
+            },
+            {
+                let log = log.clone();
+
+                move |_| log.borrow_mut().push(("hidden", false))
+            },
+        );
+
+        runtime.flush().unwrap();
+        assert_eq!(*log.borrow(), [("once", false), ("hidden", false)]);
+        assert!(once.is_disposed());
+        assert!(once.dependent().dependencies().is_empty());
+
+        visible.set(true);
+        runtime.flush().unwrap();
+        assert!(while_hidden.is_disposed());
+
+        visible.set(false);
+        runtime.flush().unwrap();
+        assert!(runtime.is_idle());
+        assert_eq!(log.borrow().len(), 2);
+    }
+
+    #[test]
+    fn test_defer() {
+        let runtime = Runtime::new();
+
+        let count = RcRx::new(0);
+        let log = Rc::new(RefCell::new(Vec::new()));
+
+        // counts up to 3, one step per run
+        let _effect = runtime.effect({
+            let runtime = runtime.clone();
+            let count = count.clone();
+            let log = log.clone();
+
+            move |ctx| {
+                let value = *count.get(ctx);
+                log.borrow_mut().push(format!("run {value}"));
+
+                if value < 3 {
+                    let count = count.clone();
+                    let log = log.clone();
+
+                    runtime.defer(move || {
+                        log.borrow_mut().push(format!("set {}", value + 1));
+                        count.set(value + 1);
+                    });
+                }
+            }
+        });
+
+        runtime.defer({
+            let log = log.clone();
+
+            move || log.borrow_mut().push("start".into())
+        });
+
+        runtime.flush().unwrap();
+
+        assert!(runtime.is_idle());
+        assert_eq!(
+            *log.borrow(),
+            ["start", "run 0", "set 1", "run 1", "set 2", "run 2", "set 3", "run 3"],
+        );
+    }
+
+    #[test]
+    fn test_phases() {
+        let runtime = Runtime::with_max_passes(3);
+
+        let width = RcRx::new(10);
+        let log = Rc::new(RefCell::new(Vec::new()));
+
+        let _render = runtime.effect_in(Phase::RENDER, {
+            let width = width.clone();
+            let log = log.clone();
+
+            move |ctx| log.borrow_mut().push(format!("render {}", width.get(ctx)))
+        });
+
+        // Clamps the width, which the state effect reads too.
+        let _layout = runtime.effect_in(Phase::LAYOUT, {
+            let width = width.clone();
+            let log = log.clone();
+
+            move |ctx| {
+                log.borrow_mut().push("layout".into());
+                let clamped = (*width.get(ctx)).min(100);
+                width.set_if_changed(clamped);
+            }
+        });
+
+        let _state = runtime.effect({
+            let width = width.clone();
+            let log = log.clone();
+
+            move |ctx| log.borrow_mut().push(format!("state {}", width.get(ctx)))
+        });
+
+        runtime.flush().unwrap();
+        assert_eq!(*log.borrow(), ["state 10", "layout", "render 10"]);
+
+        log.borrow_mut().clear();
+        width.set(200);
+        runtime.flush().unwrap();
+        assert_eq!(
+            *log.borrow(),
+            ["state 200", "layout", "state 100", "layout", "render 100"],
+        );
+
+        // this one never settles
+        let _grow = runtime.effect_in(Phase::RENDER, {
+            let width = width.clone();
+
+            move |ctx| {
+                let grown = *width.get(ctx) + 1;
+                width.set(grown);
+            }
+        });
+
+        assert!(matches!(runtime.flush(), Err(FlushError::TooManyPasses)));
+        assert!(!runtime.is_idle());
+    }
+
+    #[test]
+    fn test_strict() {
+        let runtime = Runtime::strict();
+
+        let count = RcRx::new(0);
+        let doubled = RcRx::new(0);
+
+        let read = runtime.effect({
+            let doubled = doubled.clone();
+
+            move |ctx| {
+                doubled.get(ctx);
+            }
+        });
+
+        let write = runtime.effect({
+            let count = count.clone();
+            let doubled = doubled.clone();
+
+            move |ctx| {
+                let value = *count.get(ctx) * 2;
+                doubled.set_if_changed(value);
+            }
+        });
+
+        runtime.flush().unwrap();
+
+        count.set(1);
+
+        let Err(FlushError::WriteAfterRead { writer, reader }) = runtime.flush() else {
+            panic!("expected an error");
+        };
+
+        assert!(Rc::ptr_eq(&writer, write.dependent()));
+        assert!(Rc::ptr_eq(&reader, read.dependent()));
+    }
+
+    #[test]
+    fn test_flush_with_budget() {
+        let runtime = Runtime::new();
+
+        let rows = RcRx::new(0);
+        let time = Rc::new(Cell::new(Duration::ZERO));
+
+        // every row takes 10ms to render
+        let effects: Vec<_> = (0..5)
+            .map(|_| {
+                runtime.effect({
+                    let rows = rows.clone();
+                    let time = time.clone();
+
+                    move |ctx| {
+                        rows.get(ctx);
+                        time.set(time.get() + Duration::from_millis(10));
+                    }
+                })
+            })
+            .collect();
+
+        let flush = |budget| {
+            runtime
+                .flush_with_budget(Duration::from_millis(budget), || time.get())
+                .unwrap()
+        };
+
+        assert_eq!(flush(20), 3);
+        assert_eq!(flush(20), 1);
+        assert_eq!(flush(20), 0);
+
+        rows.set(1);
+        assert_eq!(flush(1000), 0);
+        assert_eq!(time.get(), Duration::from_millis(100));
+
+        drop(effects);
+    }
+
+    #[test]
+    fn test_lanes() {
+        let runtime = Runtime::new();
+
+        let data = RcRx::new(0);
+        let rendered = Rc::new(RefCell::new(Vec::new()));
+
+        let rows: Vec<_> = (0..3)
+            .map(|i| {
+                runtime.effect({
+                    let data = data.clone();
+                    let rendered = rendered.clone();
+
+                    move |ctx| rendered.borrow_mut().push((i, *data.get(ctx)))
+                })
+            })
+            .collect();
+
+        // the list got scrolled so that only the first row is on screen
+        rows[1].set_lane(Lane::OFFSCREEN);
+        rows[2].set_lane(Lane::OFFSCREEN);
+
+        runtime.flush_lane(Lane::VISIBLE).unwrap();
+        assert_eq!(rendered.take(), [(0, 0)]);
+        assert!(!runtime.is_idle());
+
+        // a row that scrolls into view while it is queued gets rendered with the visible ones
+        rows[2].set_lane(Lane::VISIBLE);
+        data.set(1);
+
+        runtime.flush_lane(Lane::VISIBLE).unwrap();
+        assert_eq!(rendered.take(), [(0, 1), (2, 1)]);
+
+        runtime.flush().unwrap();
+        assert_eq!(rendered.take(), [(1, 1)]);
+        assert!(runtime.is_idle());
+    }
+}
@@ -0,0 +1,116 @@
+use std::{borrow::Cow, cell::Cell, rc::Rc};
+
+use crate::{Dependent, Dependents, RxCtx};
+
+/// Like [`crate::Rx`], but for small [`Copy`] values. The value gets returned by value and can be
+/// set through a shared reference.
+#[derive(Debug)]
+pub struct RxCell<T: Copy> {
+    value: Cell<T>,
+    dependents: Dependents,
+}
+
+impl<T: Copy> Clone for RxCell<T> {
+    fn clone(&self) -> Self {
+        RxCell {
+            value: self.value.clone(),
+            dependents: Dependents::with_name(self.dependents.0.name.clone()),
+        }
+    }
+}
+
+impl<T: Copy + Default> Default for RxCell<T> {
+    fn default() -> Self {
+        RxCell::new(T::default())
+    }
+}
+
+impl<T: Copy> RxCell<T> {
+    pub fn new(value: T) -> Self {
+        RxCell {
+            value: Cell::new(value),
+            dependents: Dependents::new(),
+        }
+    }
+
+    /// The name shows up in the debug output and the graph exported by [`crate::debug::to_dot`].
+    pub fn new_named(name: impl Into<Cow<'static, str>>, value: T) -> Self {
+        RxCell {
+            value: Cell::new(value),
+            dependents: Dependents::new_named(name),
+        }
+    }
+
+    pub fn get(&self, ctx: &RxCtx) -> T {
+        self.dependents.track(ctx);
+
+        self.value.get()
+    }
+
+    /// See [`crate::Rx::get_untracked`].
+    #[track_caller]
+    pub fn get_untracked(&self) -> T {
+        crate::hazards::check_untracked_read();
+
+        self.value.get()
+    }
+
+    pub fn get_untracked_intentional(&self) -> T {
+        self.value.get()
+    }
+
+    /// Everything that currently depends on this.
+    pub fn dependents(&self) -> Vec<Rc<Dependent>> {
+        self.dependents.dependents()
+    }
+
+    /// Only invalidates the dependents if the new value differs from the current one. Returns
+    /// whether that was the case.
+    #[track_caller]
+    pub fn set(&self, value: T) -> bool
+    where
+        T: PartialEq,
+    {
+        if self.value.get() == value {
+            return false;
+        }
+
+        self.value.set(value);
+        self.dependents.mark_dirty();
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RxFn;
+
+    #[test]
+    fn test_rx_cell() {
+        let a = RxCell::new(1.5);
+        let times_called = Cell::new(0);
+
+        let mut f = RxFn::new();
+        let mut double = |ctx| -> f64 {
+            *f.call(ctx, (), |ctx, ()| {
+                times_called.set(times_called.get() + 1);
+                a.get(ctx) * 2.
+            })
+        };
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(double(ctx), 3.);
+
+        assert!(!a.set(1.5));
+        assert_eq!(double(ctx), 3.);
+        assert_eq!(times_called.get(), 1);
+
+        assert!(a.set(2.));
+        assert_eq!(double(ctx), 4.);
+        assert_eq!(times_called.get(), 2);
+    }
+}
@@ -0,0 +1,135 @@
+use std::{
+    borrow::Cow,
+    cell::{Ref, RefCell},
+    error::Error,
+    fmt,
+    rc::Rc,
+};
+
+use crate::{Dependent, RxCtx, RxFn};
+
+/// Returned by [`RxFnCell::call`] if it gets called again from within its own closure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CycleError;
+
+impl fmt::Display for CycleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RxFnCell was called from within its own evaluation")
+    }
+}
+
+impl Error for CycleError {}
+
+/// An [`RxFn`] that can be called through a shared reference, for computations that can end up
+/// depending on themselves (like cells in a spreadsheet).
+#[derive(Debug)]
+pub struct RxFnCell<I: PartialEq, O> {
+    // Kept outside of the RefCell so that we can still be tracked while we're being evaluated.
+    this: Rc<Dependent>,
+    rx_fn: RefCell<RxFn<I, O>>,
+}
+
+impl<I: PartialEq, O> Default for RxFnCell<I, O> {
+    fn default() -> Self {
+        RxFnCell::new()
+    }
+}
+
+impl<I: PartialEq, O> Clone for RxFnCell<I, O> {
+    fn clone(&self) -> Self {
+        RxFnCell::from_rx_fn(RxFn::with_name(self.this.dependents.0.name.clone()))
+    }
+}
+
+impl<I: PartialEq, O> RxFnCell<I, O> {
+    pub fn new() -> Self {
+        RxFnCell::from_rx_fn(RxFn::new())
+    }
+
+    /// The name shows up in the debug output and the graph exported by [`crate::debug::to_dot`].
+    pub fn new_named(name: impl Into<Cow<'static, str>>) -> Self {
+        RxFnCell::from_rx_fn(RxFn::new_named(name))
+    }
+
+    fn from_rx_fn(rx_fn: RxFn<I, O>) -> Self {
+        RxFnCell {
+            this: rx_fn.this.clone(),
+            rx_fn: RefCell::new(rx_fn),
+        }
+    }
+
+    /// Like [`RxFn::call`], but returns a [`CycleError`] instead of panicking if this is called
+    /// from within its own closure.
+    pub fn call(
+        &self,
+        ctx: &RxCtx,
+        params: I,
+        closure: impl FnMut(&RxCtx, &I) -> O,
+    ) -> Result<Ref<'_, O>, CycleError> {
+        let Ok(mut rx_fn) = self.rx_fn.try_borrow_mut() else {
+            // The caller still has to depend on us. Otherwise it wouldn't get invalidated if we
+            // change in a way that breaks the cycle.
+            self.this.dependents.track(ctx);
+
+            return Err(CycleError);
+        };
+
+        rx_fn.call(ctx, params, closure);
+        drop(rx_fn);
+
+        // The unwrap works because call always leaves a result behind.
+        Ok(Ref::map(self.rx_fn.borrow(), |rx_fn| {
+            rx_fn.result.as_ref().unwrap()
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rx;
+
+    #[test]
+    fn test_cycle() {
+        // a = b, b = a or a constant depending on the switch
+        struct State {
+            switch: Rx<bool>,
+            a: RxFnCell<(), Result<u32, CycleError>>,
+            b: RxFnCell<(), Result<u32, CycleError>>,
+        }
+
+        fn a(ctx: &RxCtx, state: &State) -> Result<u32, CycleError> {
+            *state.a.call(ctx, (), |ctx, ()| b(ctx, state))?
+        }
+
+        fn b(ctx: &RxCtx, state: &State) -> Result<u32, CycleError> {
+            *state.b.call(ctx, (), |ctx, ()| {
+                if *state.switch.get(ctx) {
+                    a(ctx, state)
+                } else {
+                    Ok(7)
+                }
+            })?
+        }
+
+        let mut state = State {
+            switch: Rx::new(true),
+            a: RxFnCell::new(),
+            b: RxFnCell::new(),
+        };
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(a(ctx, &state), Err(CycleError));
+
+        state.switch.set(false);
+
+        assert_eq!(a(ctx, &state), Ok(7));
+        assert_eq!(b(ctx, &state), Ok(7));
+
+        state.switch.set(true);
+
+        assert_eq!(b(ctx, &state), Err(CycleError));
+    }
+}
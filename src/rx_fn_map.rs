@@ -0,0 +1,124 @@
+use std::{collections::HashMap, hash::Hash};
+
+use crate::{RxCtx, RxFn};
+
+/// One [`RxFn`] per key, for when the same computation is needed for many different things (like
+/// a query per entity id). Every key tracks its own dependencies.
+///
+/// Entries for keys that stop being used can be removed with [`RxFnMap::evict_unused`].
+#[derive(Debug)]
+pub struct RxFnMap<K, I: PartialEq, O> {
+    entries: HashMap<K, Entry<I, O>>,
+}
+
+#[derive(Debug)]
+struct Entry<I: PartialEq, O> {
+    rx_fn: RxFn<I, O>,
+    used: bool,
+}
+
+impl<K: Eq + Hash, I: PartialEq, O> Default for RxFnMap<K, I, O> {
+    fn default() -> Self {
+        RxFnMap::new()
+    }
+}
+
+impl<K: Eq + Hash, I: PartialEq, O> RxFnMap<K, I, O> {
+    pub fn new() -> Self {
+        RxFnMap {
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Like [`RxFn::call`] on the entry for `key`, which gets created if it doesn't exist yet.
+    pub fn call(
+        &mut self,
+        ctx: &RxCtx,
+        key: K,
+        params: I,
+        closure: impl FnMut(&RxCtx, &I) -> O,
+    ) -> &O {
+        let entry = self.entries.entry(key).or_insert_with(|| Entry {
+            rx_fn: RxFn::new(),
+            used: false,
+        });
+
+        entry.used = true;
+
+        entry.rx_fn.call(ctx, params, closure)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn remove(&mut self, key: &K) {
+        self.entries.remove(key);
+    }
+
+    /// Removes the entries that weren't called since the last time this was called. Calling this
+    /// once per frame (or whatever the unit of work is) keeps only what's still needed.
+    pub fn evict_unused(&mut self) {
+        self.entries
+            .retain(|_, entry| std::mem::take(&mut entry.used));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::{Dependent, Rx};
+
+    #[test]
+    fn test_rx_fn_map() {
+        let mut values = [Rx::new(1), Rx::new(2)];
+
+        let times_called = Cell::new(0);
+
+        let mut map = RxFnMap::new();
+        let mut double = |ctx, values: &[Rx<u32>], i: usize| -> u32 {
+            *map.call(ctx, i, (), |ctx, ()| {
+                times_called.set(times_called.get() + 1);
+                values[i].get(ctx) * 2
+            })
+        };
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(double(ctx, &values, 0), 2);
+        assert_eq!(double(ctx, &values, 1), 4);
+        assert_eq!(times_called.get(), 2);
+
+        values[1].set(3);
+
+        assert_eq!(double(ctx, &values, 0), 2);
+        assert_eq!(double(ctx, &values, 1), 6);
+        assert_eq!(times_called.get(), 3);
+    }
+
+    #[test]
+    fn test_evict_unused() {
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        let mut map = RxFnMap::new();
+
+        map.call(ctx, 0, (), |_, ()| 0);
+        map.call(ctx, 1, (), |_, ()| 1);
+        map.evict_unused();
+
+        assert_eq!(map.len(), 2);
+
+        map.call(ctx, 1, (), |_, ()| 1);
+        map.evict_unused();
+
+        assert_eq!(map.len(), 1);
+    }
+}
@@ -0,0 +1,134 @@
+use std::{cell::Ref, rc::Rc};
+
+use crate::{Dependent, RcRx, RxCtx};
+
+/// Creates reactive state that lives behind an [`Rc`] and can be read and written through
+/// separate handles. Both of them are cheap to clone, so the writer can live wherever events get
+/// handled while the readers are used in computations. This is an [`RcRx`] split in two, for
+/// code that wants to make clear which parts of it can write.
+pub fn rx_pair<T>(value: T) -> (ReadHandle<T>, WriteHandle<T>) {
+    let rx = RcRx::new(value);
+
+    (ReadHandle(rx.clone()), WriteHandle(rx))
+}
+
+/// The reading side of [`rx_pair`].
+#[derive(Debug)]
+pub struct ReadHandle<T>(RcRx<T>);
+
+/// The writing side of [`rx_pair`].
+#[derive(Debug)]
+pub struct WriteHandle<T>(RcRx<T>);
+
+impl<T> Clone for ReadHandle<T> {
+    fn clone(&self) -> Self {
+        ReadHandle(self.0.clone())
+    }
+}
+
+impl<T> Clone for WriteHandle<T> {
+    fn clone(&self) -> Self {
+        WriteHandle(self.0.clone())
+    }
+}
+
+impl<T> ReadHandle<T> {
+    /// See [`RcRx::get`].
+    pub fn get(&self, ctx: &RxCtx) -> Ref<'_, T> {
+        self.0.get(ctx)
+    }
+
+    /// See [`crate::Rx::get_untracked`].
+    #[track_caller]
+    pub fn get_untracked(&self) -> Ref<'_, T> {
+        self.0.get_untracked()
+    }
+
+    pub fn get_untracked_intentional(&self) -> Ref<'_, T> {
+        self.0.get_untracked_intentional()
+    }
+
+    /// Everything that currently depends on this.
+    pub fn dependents(&self) -> Vec<Rc<Dependent>> {
+        self.0.dependents()
+    }
+}
+
+impl<T> WriteHandle<T> {
+    /// See [`crate::Rx::get_untracked`].
+    #[track_caller]
+    pub fn get_untracked(&self) -> Ref<'_, T> {
+        self.0.get_untracked()
+    }
+
+    pub fn get_untracked_intentional(&self) -> Ref<'_, T> {
+        self.0.get_untracked_intentional()
+    }
+
+    #[track_caller]
+    pub fn set(&self, value: T) {
+        self.0.set(value);
+    }
+
+    /// See [`RcRx::set_if_changed`].
+    #[track_caller]
+    pub fn set_if_changed(&self, value: T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.0.set_if_changed(value)
+    }
+
+    /// See [`RcRx::update`].
+    #[track_caller]
+    pub fn update<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        self.0.update(f)
+    }
+
+    /// Creates another handle for reading.
+    pub fn reader(&self) -> ReadHandle<T> {
+        ReadHandle(self.0.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::RxFn;
+
+    #[test]
+    fn test_rx_pair() {
+        let (read, write) = rx_pair(1);
+        let times_called = Cell::new(0);
+
+        let mut f = RxFn::new();
+        let mut double = |ctx| -> u32 {
+            *f.call(ctx, (), |ctx, ()| {
+                times_called.set(times_called.get() + 1);
+                *read.get(ctx) * 2
+            })
+        };
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(double(ctx), 2);
+
+        let on_click = {
+            let write = write.clone();
+
+            move || write.update(|v| *v += 1)
+        };
+
+        assert!(!write.set_if_changed(1));
+        assert_eq!(double(ctx), 2);
+        assert_eq!(times_called.get(), 1);
+
+        on_click();
+
+        assert_eq!(double(ctx), 4);
+        assert_eq!(times_called.get(), 2);
+    }
+}
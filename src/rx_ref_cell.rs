@@ -0,0 +1,160 @@
+use std::{
+    borrow::Cow,
+    cell::{Ref, RefCell, RefMut},
+    fmt,
+    ops::{Deref, DerefMut},
+    rc::Rc,
+};
+
+use crate::{Dependent, Dependents, RxCtx};
+
+/// Like [`crate::Rx`], but the value can be mutated through a shared reference, so it doesn't have
+/// to be passed down mutably through everything that might change it.
+#[derive(Debug, Default)]
+pub struct RxRefCell<T> {
+    value: RefCell<T>,
+    dependents: Dependents,
+}
+
+impl<T: Clone> Clone for RxRefCell<T> {
+    fn clone(&self) -> Self {
+        RxRefCell {
+            value: self.value.clone(),
+            dependents: Dependents::with_name(self.dependents.0.name.clone()),
+        }
+    }
+}
+
+impl<T> RxRefCell<T> {
+    pub fn new(value: T) -> Self {
+        RxRefCell {
+            value: RefCell::new(value),
+            dependents: Dependents::new(),
+        }
+    }
+
+    /// The name shows up in the debug output and the graph exported by [`crate::debug::to_dot`].
+    pub fn new_named(name: impl Into<Cow<'static, str>>, value: T) -> Self {
+        RxRefCell {
+            value: RefCell::new(value),
+            dependents: Dependents::new_named(name),
+        }
+    }
+
+    /// # Panics
+    ///
+    /// If the value is borrowed mutably at the same time.
+    pub fn get(&self, ctx: &RxCtx) -> Ref<'_, T> {
+        self.dependents.track(ctx);
+
+        self.value.borrow()
+    }
+
+    /// See [`crate::Rx::get_untracked`].
+    #[track_caller]
+    pub fn get_untracked(&self) -> Ref<'_, T> {
+        crate::hazards::check_untracked_read();
+
+        self.value.borrow()
+    }
+
+    pub fn get_untracked_intentional(&self) -> Ref<'_, T> {
+        self.value.borrow()
+    }
+
+    /// Borrows the value for mutating it. The dependents only get invalidated when the guard gets
+    /// dropped, and only if it was actually used mutably.
+    ///
+    /// # Panics
+    ///
+    /// If the value is borrowed at the same time.
+    #[track_caller]
+    pub fn borrow_mut(&self) -> RxRefMut<'_, T> {
+        crate::hazards::check_write();
+
+        RxRefMut {
+            value: self.value.borrow_mut(),
+            dependents: &self.dependents,
+            written: false,
+        }
+    }
+
+    #[track_caller]
+    pub fn set(&self, value: T) {
+        *self.borrow_mut() = value;
+    }
+
+    /// Everything that currently depends on this.
+    pub fn dependents(&self) -> Vec<Rc<Dependent>> {
+        self.dependents.dependents()
+    }
+}
+
+/// The guard returned by [`RxRefCell::borrow_mut`].
+pub struct RxRefMut<'a, T> {
+    value: RefMut<'a, T>,
+    dependents: &'a Dependents,
+    written: bool,
+}
+
+impl<T: fmt::Debug> fmt::Debug for RxRefMut<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+impl<T> Deref for RxRefMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for RxRefMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.written = true;
+
+        &mut self.value
+    }
+}
+
+impl<T> Drop for RxRefMut<'_, T> {
+    fn drop(&mut self) {
+        // This was already checked in RxRefCell::borrow_mut, where the location is still known.
+        if self.written {
+            self.dependents.mark_dirty_unchecked();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RxFn;
+
+    #[test]
+    fn test_rx_ref_cell() {
+        let items = RxRefCell::new(vec![1, 2]);
+        let mut f = RxFn::new();
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        let mut sum = |ctx| *f.call(ctx, (), |ctx, ()| items.get(ctx).iter().sum::<i32>());
+
+        assert_eq!(sum(ctx), 3);
+        dependent.take_dirty();
+
+        // only reading through the guard doesn't invalidate anything
+        assert_eq!(items.borrow_mut().len(), 2);
+        assert!(!dependent.dirty());
+
+        items.borrow_mut().push(3);
+        assert!(dependent.take_dirty());
+        assert_eq!(sum(ctx), 6);
+
+        items.set(Vec::new());
+        assert_eq!(sum(ctx), 0);
+    }
+}
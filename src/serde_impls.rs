@@ -0,0 +1,70 @@
+//! Only the values get (de)serialized. Deserialized reactives start out without any dependents.
+
+use std::{collections::HashMap, hash::Hash};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{Dependents, Rx, RxHashMap};
+
+impl<T: Serialize> Serialize for Rx<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Rx<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Rx::with_dependents(
+            T::deserialize(deserializer)?,
+            Dependents::new(),
+        ))
+    }
+}
+
+impl<K: Serialize, V: Serialize> Serialize for RxHashMap<K, V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.map.serialize(serializer)
+    }
+}
+
+impl<'de, K, V> Deserialize<'de> for RxHashMap<K, V>
+where
+    K: Deserialize<'de> + Clone + Eq + Hash,
+    V: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut rx_map = RxHashMap::new();
+        rx_map.map = HashMap::deserialize(deserializer)?;
+
+        Ok(rx_map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Dependent;
+
+    #[test]
+    fn test_round_trip() {
+        let a = Rx::new(3);
+
+        let mut b = RxHashMap::new();
+        b.insert("x".to_string(), 1.5);
+
+        let a_json = serde_json::to_string(&a).unwrap();
+        let b_json = serde_json::to_string(&b).unwrap();
+
+        assert_eq!(a_json, "3");
+        assert_eq!(b_json, r#"{"x":1.5}"#);
+
+        let a: Rx<u32> = serde_json::from_str(&a_json).unwrap();
+        let b: RxHashMap<String, f64> = serde_json::from_str(&b_json).unwrap();
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(*a.get(ctx), 3);
+        assert_eq!(b.get(ctx, &"x".to_string()), Some(&1.5));
+    }
+}
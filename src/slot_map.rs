@@ -0,0 +1,226 @@
+use crate::{Dependents, RxCtx};
+
+/// Returned by [`RxSlotMap::insert`]. Stays valid until the value gets removed and is never
+/// confused with a key of a value that gets inserted later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SlotKey {
+    index: u32,
+    generation: u32,
+}
+
+/// A reactive slot map, for entity-like state where things need a stable key.
+///
+/// Every key has its own list of dependents, so changing or removing a value only invalidates the
+/// readers of that key. Operations that change the set of keys additionally notify the dependents
+/// that looked at the map as a whole (like through [`RxSlotMap::keys`] or [`RxSlotMap::len`]).
+#[derive(Debug)]
+pub struct RxSlotMap<T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<u32>,
+    len: usize,
+    structure: Dependents,
+}
+
+#[derive(Debug)]
+struct Slot<T> {
+    generation: u32,
+    value: Option<T>,
+    dependents: Dependents,
+}
+
+impl<T> Default for RxSlotMap<T> {
+    fn default() -> Self {
+        RxSlotMap::new()
+    }
+}
+
+impl<T> RxSlotMap<T> {
+    pub fn new() -> Self {
+        RxSlotMap {
+            slots: Vec::new(),
+            free: Vec::new(),
+            len: 0,
+            structure: Dependents::new(),
+        }
+    }
+
+    fn slot(&self, key: SlotKey) -> Option<&Slot<T>> {
+        self.slots
+            .get(key.index as usize)
+            .filter(|slot| slot.generation == key.generation)
+    }
+
+    fn slot_mut(&mut self, key: SlotKey) -> Option<&mut Slot<T>> {
+        self.slots
+            .get_mut(key.index as usize)
+            .filter(|slot| slot.generation == key.generation)
+    }
+
+    /// Only gets invalidated when this value changes or gets removed.
+    pub fn get(&self, ctx: &RxCtx, key: SlotKey) -> Option<&T> {
+        // Once the generation doesn't match anymore the key is gone for good.
+        let slot = self.slot(key)?;
+        slot.dependents.track(ctx);
+
+        slot.value.as_ref()
+    }
+
+    pub fn contains_key(&self, ctx: &RxCtx, key: SlotKey) -> bool {
+        self.get(ctx, key).is_some()
+    }
+
+    /// Only gets invalidated when values are inserted or removed.
+    pub fn len(&self, ctx: &RxCtx) -> usize {
+        self.structure.track(ctx);
+
+        self.len
+    }
+
+    pub fn is_empty(&self, ctx: &RxCtx) -> bool {
+        self.len(ctx) == 0
+    }
+
+    /// Only gets invalidated when values are inserted or removed, not when they change.
+    pub fn keys(&self, ctx: &RxCtx) -> impl Iterator<Item = SlotKey> + '_ {
+        self.structure.track(ctx);
+
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            slot.value.as_ref().map(|_| SlotKey {
+                index: index as u32,
+                generation: slot.generation,
+            })
+        })
+    }
+
+    /// Subscribes to the set of keys and to every value.
+    pub fn iter(&self, ctx: &RxCtx) -> impl Iterator<Item = (SlotKey, &T)> {
+        self.structure.track(ctx);
+
+        for slot in &self.slots {
+            if slot.value.is_some() {
+                slot.dependents.track(ctx);
+            }
+        }
+
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            let key = SlotKey {
+                index: index as u32,
+                generation: slot.generation,
+            };
+
+            slot.value.as_ref().map(|value| (key, value))
+        })
+    }
+
+    #[track_caller]
+    pub fn get_mut(&mut self, key: SlotKey) -> Option<&mut T> {
+        let slot = self.slot_mut(key)?;
+        let value = slot.value.as_mut()?;
+
+        slot.dependents.mark_dirty();
+
+        Some(value)
+    }
+
+    #[track_caller]
+    pub fn insert(&mut self, value: T) -> SlotKey {
+        let key = match self.free.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index as usize];
+                slot.value = Some(value);
+
+                SlotKey {
+                    index,
+                    generation: slot.generation,
+                }
+            }
+            None => {
+                let index =
+                    u32::try_from(self.slots.len()).expect("RxSlotMap can't hold more slots");
+
+                self.slots.push(Slot {
+                    generation: 0,
+                    value: Some(value),
+                    dependents: Dependents::new(),
+                });
+
+                SlotKey {
+                    index,
+                    generation: 0,
+                }
+            }
+        };
+
+        self.len += 1;
+        self.structure.mark_dirty();
+
+        key
+    }
+
+    #[track_caller]
+    pub fn remove(&mut self, key: SlotKey) -> Option<T> {
+        let slot = self.slot_mut(key)?;
+        let value = slot.value.take()?;
+
+        slot.dependents.mark_dirty();
+
+        // A slot whose generation would overflow doesn't get reused, so old keys stay invalid.
+        if let Some(generation) = slot.generation.checked_add(1) {
+            slot.generation = generation;
+            self.free.push(key.index);
+        }
+
+        self.len -= 1;
+        self.structure.mark_dirty();
+
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::{Dependent, RxFn};
+
+    #[test]
+    fn test_slot_map() {
+        let mut map = RxSlotMap::new();
+
+        let a = map.insert(1);
+        let b = map.insert(2);
+
+        let times_called = Cell::new(0);
+
+        let mut f = RxFn::new();
+        let mut get = |ctx, map: &RxSlotMap<u32>| -> Option<u32> {
+            *f.call(ctx, (), |ctx, ()| {
+                times_called.set(times_called.get() + 1);
+                map.get(ctx, a).copied()
+            })
+        };
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(get(ctx, &map), Some(1));
+
+        *map.get_mut(b).unwrap() = 3;
+        map.remove(b);
+        let c = map.insert(4);
+
+        assert_eq!(get(ctx, &map), Some(1));
+        assert_eq!(times_called.get(), 1);
+
+        // the slot of b got reused, but b doesn't refer to c
+        assert_ne!(b, c);
+        assert_eq!(map.get(ctx, b), None);
+        assert_eq!(map.len(ctx), 2);
+
+        map.remove(a);
+
+        assert_eq!(get(ctx, &map), None);
+        assert_eq!(times_called.get(), 2);
+    }
+}
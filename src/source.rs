@@ -0,0 +1,74 @@
+use crate::{MaybeRx, Memo, ReadRx, Rx, RxCell, RxCtx};
+
+/// Something a value can be read from, either reactively or not at all.
+///
+/// This is for APIs like widgets that want to accept either a fixed value or a reactive one.
+/// Every type is a source of itself that never changes, and the reactive types are sources of
+/// their value that get tracked when they're read.
+pub trait Source<T> {
+    fn with<R>(&mut self, ctx: &RxCtx, f: impl FnOnce(&T) -> R) -> R;
+}
+
+impl<T> Source<T> for T {
+    fn with<R>(&mut self, _ctx: &RxCtx, f: impl FnOnce(&T) -> R) -> R {
+        f(self)
+    }
+}
+
+impl<T: Clone> Source<T> for Rx<T> {
+    fn with<R>(&mut self, ctx: &RxCtx, f: impl FnOnce(&T) -> R) -> R {
+        f(self.get(ctx))
+    }
+}
+
+impl<T: Clone> Source<T> for ReadRx<'_, T> {
+    fn with<R>(&mut self, ctx: &RxCtx, f: impl FnOnce(&T) -> R) -> R {
+        f(self.get(ctx))
+    }
+}
+
+impl<T: Copy> Source<T> for RxCell<T> {
+    fn with<R>(&mut self, ctx: &RxCtx, f: impl FnOnce(&T) -> R) -> R {
+        f(&self.get(ctx))
+    }
+}
+
+impl<T: Clone> Source<T> for MaybeRx<'_, T> {
+    fn with<R>(&mut self, ctx: &RxCtx, f: impl FnOnce(&T) -> R) -> R {
+        f(self.get(ctx))
+    }
+}
+
+impl<O> Source<O> for Memo<'_, O> {
+    fn with<R>(&mut self, ctx: &RxCtx, f: impl FnOnce(&O) -> R) -> R {
+        f(self.get(ctx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Dependent;
+
+    fn label(ctx: &RxCtx, text: &mut impl Source<String>) -> String {
+        text.with(ctx, |text| format!("[{text}]"))
+    }
+
+    #[test]
+    fn test_source() {
+        let mut text = Rx::new(String::from("a"));
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(label(ctx, &mut String::from("fixed")), "[fixed]");
+        assert_eq!(label(ctx, &mut text), "[a]");
+
+        dependent.take_dirty();
+        text.set(String::from("b"));
+        assert!(dependent.dirty());
+        assert_eq!(
+            label(ctx, &mut Memo::new(|ctx| text.get(ctx).repeat(2))),
+            "[bb]"
+        );
+    }
+}
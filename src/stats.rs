@@ -0,0 +1,169 @@
+//! Counters for checking whether memoization boundaries actually save work. Only available with
+//! the `stats` feature. With the `profiling` feature the time computations take gets measured as
+//! well.
+
+use std::cell::Cell;
+#[cfg(feature = "profiling")]
+use std::{collections::HashSet, rc::Rc, time::Duration};
+
+#[cfg(feature = "profiling")]
+use crate::{Dependency, Dependent};
+
+/// How often computations were rerun versus served from their cache.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub recomputes: u64,
+    pub cache_hits: u64,
+}
+
+thread_local! {
+    static TOTAL: Cell<Stats> = const {
+        Cell::new(Stats {
+            recomputes: 0,
+            cache_hits: 0,
+        })
+    };
+}
+
+/// The counters summed over all computations on the current thread since the last [`reset`].
+pub fn total() -> Stats {
+    TOTAL.get()
+}
+
+/// Sets the thread-wide counters back to zero. The counters of the individual computations are
+/// left alone.
+pub fn reset() {
+    TOTAL.set(Stats::default());
+}
+
+pub(crate) fn record(stats: &Cell<Stats>, recomputed: bool) {
+    let bump = |mut stats: Stats| {
+        if recomputed {
+            stats.recomputes += 1;
+        } else {
+            stats.cache_hits += 1;
+        }
+
+        stats
+    };
+
+    stats.set(bump(stats.get()));
+    TOTAL.set(bump(TOTAL.get()));
+}
+
+/// How long a computation took to run.
+#[cfg(feature = "profiling")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Timing {
+    /// The latest run.
+    pub last: Duration,
+    /// All runs added up.
+    pub total: Duration,
+}
+
+#[cfg(feature = "profiling")]
+pub(crate) fn record_time(timing: &Cell<Timing>, elapsed: Duration) {
+    let Timing { total, .. } = timing.get();
+
+    timing.set(Timing {
+        last: elapsed,
+        total: total + elapsed,
+    });
+}
+
+/// The `n` computations that `roots` (transitively) depend on that took the most time in total,
+/// starting with the most expensive one. The time of a computation includes the time of the ones
+/// it called.
+#[cfg(feature = "profiling")]
+pub fn most_expensive(roots: &[Rc<Dependent>], n: usize) -> Vec<Rc<Dependent>> {
+    let mut seen = HashSet::new();
+    let mut found = Vec::new();
+    let mut worklist = roots.to_vec();
+
+    while let Some(dependent) = worklist.pop() {
+        for computation in dependent
+            .dependencies()
+            .iter()
+            .filter_map(Dependency::computation)
+        {
+            if seen.insert(Rc::as_ptr(&computation)) {
+                found.push(computation.clone());
+                worklist.push(computation);
+            }
+        }
+    }
+
+    found.sort_by_key(|d| std::cmp::Reverse(d.timing().total));
+    found.truncate(n);
+
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Dependent, Rx, RxFn};
+
+    #[test]
+    fn test_stats() {
+        reset();
+
+        let mut a = Rx::new(1);
+        let mut f = RxFn::new();
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        f.call(ctx, (), |ctx, ()| *a.get(ctx));
+        f.call(ctx, (), |ctx, ()| *a.get(ctx));
+        a.set(2);
+        f.call(ctx, (), |ctx, ()| *a.get(ctx));
+
+        let expected = Stats {
+            recomputes: 2,
+            cache_hits: 1,
+        };
+
+        assert_eq!(f.dependent().stats(), expected);
+        assert_eq!(total(), expected);
+
+        reset();
+
+        assert_eq!(total(), Stats::default());
+        assert_eq!(f.dependent().stats(), expected);
+    }
+
+    #[cfg(feature = "profiling")]
+    #[test]
+    fn test_most_expensive() {
+        let mut a = Rx::new(1);
+        let mut cheap = RxFn::new_named("cheap");
+        let mut slow = RxFn::new_named("slow");
+
+        let root = Dependent::toplevel();
+
+        let mut frame = |a: &Rx<i32>| {
+            root.frame(|ctx| {
+                cheap.call(ctx, (), |ctx, ()| *a.get(ctx));
+                slow.call(ctx, (), |ctx, ()| {
+                    std::thread::sleep(Duration::from_millis(5));
+                    *a.get(ctx)
+                });
+            });
+
+            let timing = slow.dependent().timing();
+
+            (timing, most_expensive(std::slice::from_ref(&root), 1))
+        };
+
+        let (first, top) = frame(&a);
+        assert!(first.last >= Duration::from_millis(5));
+        assert_eq!(first.last, first.total);
+        assert_eq!(top[0].name(), Some("slow"));
+
+        a.set(2);
+
+        let (second, _) = frame(&a);
+        assert_eq!(second.total, first.total + second.last);
+    }
+}
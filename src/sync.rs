@@ -0,0 +1,178 @@
+//! Signals that can be written from other threads. Only available with the `sync` feature.
+//!
+//! The dependency graph itself stays on one thread. A [`SyncRx`] can be cloned and sent to
+//! background threads, while the thread that owns the graph reads it through a [`SyncRxReader`]
+//! that has to be [polled](SyncRxReader::poll) to pick up changes.
+
+use std::{
+    borrow::Cow,
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, RwLock,
+    },
+};
+
+use crate::{Rx, RxCtx};
+
+/// A thread-safe handle to a value that a [`SyncRxReader`] exposes to the reactive graph.
+pub struct SyncRx<T>(Arc<Shared<T>>);
+
+struct Shared<T> {
+    value: RwLock<T>,
+    // Only changed while holding the write lock, so it always matches the value.
+    version: AtomicU64,
+    on_change: Mutex<Option<Box<dyn Fn() + Send + Sync>>>,
+}
+
+impl<T: fmt::Debug> fmt::Debug for SyncRx<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SyncRx")
+            .field("value", &self.0.value)
+            .field("version", &self.0.version)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Clone for SyncRx<T> {
+    fn clone(&self) -> Self {
+        SyncRx(self.0.clone())
+    }
+}
+
+impl<T: Clone> SyncRx<T> {
+    pub fn new(value: T) -> Self {
+        SyncRx(Arc::new(Shared {
+            value: RwLock::new(value),
+            version: AtomicU64::new(0),
+            on_change: Mutex::new(None),
+        }))
+    }
+
+    /// Creates a reader for the current thread. It starts out with the current value.
+    pub fn reader(&self) -> SyncRxReader<T> {
+        SyncRxReader::with_rx(self.clone(), Rx::new)
+    }
+
+    /// Like [`SyncRx::reader`], but the reader shows up with a name in the debug output and the
+    /// graph exported by [`crate::debug::to_dot`].
+    pub fn reader_named(&self, name: impl Into<Cow<'static, str>>) -> SyncRxReader<T> {
+        SyncRxReader::with_rx(self.clone(), |value| Rx::new_named(name, value))
+    }
+
+    /// Reads the value without going through the reactive graph. Works from any thread.
+    pub fn get(&self) -> T {
+        self.0.value.read().unwrap().clone()
+    }
+
+    pub fn set(&self, value: T) {
+        self.update(|v| *v = value);
+    }
+
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        let mut value = self.0.value.write().unwrap();
+        f(&mut value);
+        self.0.version.fetch_add(1, Ordering::Release);
+        drop(value);
+
+        if let Some(callback) = &*self.0.on_change.lock().unwrap() {
+            callback();
+        }
+    }
+
+    /// Sets a callback that gets called on the writing thread after every change. This is meant
+    /// for waking up the thread that owns the graph (for example by requesting a repaint) so that
+    /// it polls its readers.
+    pub fn on_change(&self, callback: impl Fn() + Send + Sync + 'static) {
+        *self.0.on_change.lock().unwrap() = Some(Box::new(callback));
+    }
+}
+
+/// The side of a [`SyncRx`] that lives on the thread that owns the reactive graph.
+#[derive(Debug)]
+pub struct SyncRxReader<T> {
+    shared: SyncRx<T>,
+    seen: u64,
+    rx: Rx<T>,
+}
+
+impl<T: Clone> SyncRxReader<T> {
+    fn with_rx(shared: SyncRx<T>, rx: impl FnOnce(T) -> Rx<T>) -> Self {
+        let value = shared.0.value.read().unwrap();
+        let seen = shared.0.version.load(Ordering::Acquire);
+        let rx = rx(value.clone());
+        drop(value);
+
+        SyncRxReader { shared, seen, rx }
+    }
+
+    /// Picks up the latest value if it changed since the last poll and invalidates everything
+    /// that depends on it. Returns whether that was the case.
+    pub fn poll(&mut self) -> bool {
+        if self.shared.0.version.load(Ordering::Acquire) == self.seen {
+            return false;
+        }
+
+        let value = self.shared.0.value.read().unwrap();
+        self.seen = self.shared.0.version.load(Ordering::Acquire);
+        self.rx.set(value.clone());
+
+        true
+    }
+
+    /// The value as of the last poll.
+    pub fn get(&self, ctx: &RxCtx) -> &T {
+        self.rx.get(ctx)
+    }
+
+    #[track_caller]
+    pub fn get_untracked(&self) -> &T {
+        self.rx.get_untracked()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, thread};
+
+    use super::*;
+    use crate::{Dependent, RxFn};
+
+    #[test]
+    fn test_sync_rx() {
+        let shared = SyncRx::new(1);
+        let mut reader = shared.reader();
+
+        let times_called = Cell::new(0);
+
+        let mut f = RxFn::new();
+        let mut double = |ctx, reader: &SyncRxReader<u32>| -> u32 {
+            *f.call(ctx, (), |ctx, ()| {
+                times_called.set(times_called.get() + 1);
+                reader.get(ctx) * 2
+            })
+        };
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(double(ctx, &reader), 2);
+        assert!(!reader.poll());
+
+        thread::spawn({
+            let shared = shared.clone();
+
+            move || shared.set(5)
+        })
+        .join()
+        .unwrap();
+
+        // nothing changes until the reader gets polled
+        assert_eq!(double(ctx, &reader), 2);
+        assert_eq!(times_called.get(), 1);
+
+        assert!(reader.poll());
+        assert_eq!(double(ctx, &reader), 10);
+        assert_eq!(times_called.get(), 2);
+    }
+}
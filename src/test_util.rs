@@ -0,0 +1,235 @@
+//! Helpers for testing code built on fluorine, mostly for checking that memoization boundaries
+//! work as intended. Only available with the `test-util` feature.
+
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use crate::{Dependent, Rx, RxCtx};
+
+/// A shared counter, for counting how often a closure runs without threading a `Cell` through
+/// everything.
+#[derive(Debug, Clone, Default)]
+pub struct Counter(Rc<Cell<u32>>);
+
+impl Counter {
+    pub fn new() -> Self {
+        Counter::default()
+    }
+
+    pub fn count(&self) {
+        self.0.set(self.0.get() + 1);
+    }
+
+    pub fn get(&self) -> u32 {
+        self.0.get()
+    }
+
+    /// Returns the count and sets it back to zero.
+    pub fn take(&self) -> u32 {
+        self.0.take()
+    }
+}
+
+/// An [`Rx`] that counts how often it gets read.
+#[derive(Debug)]
+pub struct CountingRx<T> {
+    rx: Rx<T>,
+    reads: Counter,
+}
+
+impl<T: Clone> CountingRx<T> {
+    pub fn new(value: T) -> Self {
+        CountingRx {
+            rx: Rx::new(value),
+            reads: Counter::new(),
+        }
+    }
+
+    pub fn get(&self, ctx: &RxCtx) -> &T {
+        self.reads.count();
+
+        self.rx.get(ctx)
+    }
+
+    #[track_caller]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.rx.get_mut()
+    }
+
+    #[track_caller]
+    pub fn set(&mut self, value: T) {
+        self.rx.set(value);
+    }
+
+    /// How often [`CountingRx::get`] was called.
+    pub fn reads(&self) -> &Counter {
+        &self.reads
+    }
+}
+
+/// Asserts that running the closure reruns the computation of `memo` (anything with a
+/// `dependent()` method, like an [`crate::RxFn`]) exactly `n` times.
+///
+/// ```
+/// use fluorine::{assert_recomputes, Dependent, Rx, RxFn};
+///
+/// let mut a = Rx::new(1);
+/// let mut f = RxFn::new();
+/// let dependent = Dependent::toplevel();
+/// let ctx = &dependent.ctx();
+///
+/// assert_recomputes!(f, 1, || {
+///     f.call(ctx, (), |ctx, ()| *a.get(ctx));
+///     f.call(ctx, (), |ctx, ()| *a.get(ctx));
+/// });
+/// ```
+#[macro_export]
+macro_rules! assert_recomputes {
+    ($memo:expr, $n:expr, $body:expr) => {{
+        let before = $memo.dependent().stats().recomputes;
+        ($body)();
+        let after = $memo.dependent().stats().recomputes;
+
+        assert_eq!(
+            after - before,
+            $n,
+            "expected {} recomputes of {}, got {}",
+            $n,
+            stringify!($memo),
+            after - before,
+        );
+    }};
+}
+
+/// The names of what `dependent` depended on during its latest run, sorted so they can be
+/// compared with a list. Unnamed dependencies show up as `"<unnamed>"`.
+///
+/// ```
+/// use fluorine::{test_util::dependency_names, Dependent, Rx, RxFn};
+///
+/// let a = Rx::new_named("a", true);
+/// let b = Rx::new_named("b", 1);
+/// let c = Rx::new_named("c", 2);
+/// let mut f = RxFn::new();
+/// let dependent = Dependent::toplevel();
+///
+/// f.call(&dependent.ctx(), (), |ctx, ()| if *a.get(ctx) { *b.get(ctx) } else { *c.get(ctx) });
+///
+/// assert_eq!(dependency_names(f.dependent()), ["a", "b"]);
+/// ```
+pub fn dependency_names(dependent: &Dependent) -> Vec<String> {
+    let mut names: Vec<String> = dependent
+        .dependencies()
+        .iter()
+        .map(|d| d.name().unwrap_or("<unnamed>").to_owned())
+        .collect();
+
+    names.sort();
+
+    names
+}
+
+thread_local! {
+    static RUNS: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+}
+
+/// Records which computations run on the current thread, in order, while it's alive. Starting a
+/// probe while another one is alive takes over the recording.
+#[derive(Debug)]
+pub struct Probe(());
+
+impl Probe {
+    pub fn start() -> Self {
+        RUNS.set(Some(Vec::new()));
+
+        Probe(())
+    }
+
+    /// The names of the computations that ran since the probe was started or this was last
+    /// called. Unnamed ones show up as `"<unnamed>"`.
+    pub fn take(&self) -> Vec<String> {
+        RUNS.with_borrow_mut(|runs| runs.as_mut().map(std::mem::take).unwrap_or_default())
+    }
+}
+
+impl Drop for Probe {
+    fn drop(&mut self) {
+        RUNS.set(None);
+    }
+}
+
+pub(crate) fn record_run(dependent: &Dependent) {
+    RUNS.with_borrow_mut(|runs| {
+        if let Some(runs) = runs {
+            runs.push(dependent.name().unwrap_or("<unnamed>").to_owned());
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RxFn;
+
+    #[test]
+    fn test_probe_and_counting_rx() {
+        let mut a = CountingRx::new(1);
+        let mut outer = RxFn::new_named("outer");
+        let mut inner = RxFn::new_named("inner");
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        let probe = Probe::start();
+
+        let mut run = |ctx, a: &CountingRx<i32>| {
+            outer.call(ctx, (), |ctx, ()| {
+                *inner.call(ctx, (), |ctx, ()| *a.get(ctx)) + 1
+            });
+        };
+
+        run(ctx, &a);
+        run(ctx, &a);
+        assert_eq!(probe.take(), ["outer", "inner"]);
+
+        a.set(2);
+        run(ctx, &a);
+        assert_eq!(probe.take(), ["outer", "inner"]);
+        assert_eq!(a.reads().get(), 2);
+
+        drop(probe);
+        a.set(3);
+        run(ctx, &a);
+        assert_eq!(Probe::start().take(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_dependency_names() {
+        let mut a = Rx::new_named("a", true);
+        let b = Rx::new_named("b", 1);
+        let c = Rx::new(2);
+        let mut f = RxFn::new();
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        let mut run = |ctx, a: &Rx<bool>| {
+            f.call(ctx, (), |ctx, ()| {
+                if *a.get(ctx) {
+                    *b.get(ctx)
+                } else {
+                    *c.get(ctx)
+                }
+            });
+
+            dependency_names(f.dependent())
+        };
+
+        assert_eq!(run(ctx, &a), ["a", "b"]);
+
+        a.set(false);
+        assert_eq!(run(ctx, &a), ["<unnamed>", "a"]);
+    }
+}
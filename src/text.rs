@@ -0,0 +1,408 @@
+use std::{borrow::Cow, cell::RefCell, ops::Range};
+
+use crate::{Dependents, RxCtx};
+
+/// Reactive text for things like editors, where a single keystroke shouldn't invalidate
+/// everything that was derived from the whole buffer.
+///
+/// Readers that only care about a part of the text (a line that gets highlighted, for example)
+/// create a [`TextRange`] for it with [`RxText::range`] and read it with [`RxText::slice`]. A
+/// range moves along with the edits before it, and an edit only invalidates the readers of the
+/// ranges it overlaps or touches. Those ranges grow to include the new text.
+///
+/// The text is stored in a gap buffer, so edits close to the previous one (like typing) don't
+/// have to move the rest of the text. Reading text that spans the position of the latest edit
+/// copies it.
+#[derive(Debug, Default)]
+pub struct RxText {
+    text: GapBuffer,
+    ranges: RefCell<Ranges>,
+    // Tracked by readers of the whole text.
+    whole: Dependents,
+}
+
+#[derive(Debug, Default)]
+struct Ranges {
+    slots: Vec<RangeSlot>,
+    free: Vec<u32>,
+}
+
+#[derive(Debug)]
+struct RangeSlot {
+    generation: u32,
+    anchored: Option<Anchored>,
+}
+
+#[derive(Debug)]
+struct Anchored {
+    range: Range<usize>,
+    dependents: Dependents,
+}
+
+/// A range of an [`RxText`] that moves along with the edits. Created with [`RxText::range`].
+/// Once it is removed it is never confused with a range that gets created later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TextRange {
+    index: u32,
+    generation: u32,
+}
+
+impl Ranges {
+    fn get(&self, range: TextRange) -> Option<&Anchored> {
+        self.slots
+            .get(range.index as usize)
+            .filter(|slot| slot.generation == range.generation)?
+            .anchored
+            .as_ref()
+    }
+}
+
+impl RxText {
+    pub fn new(text: impl Into<String>) -> Self {
+        RxText {
+            text: GapBuffer::new(text.into()),
+            ranges: RefCell::new(Ranges::default()),
+            whole: Dependents::new(),
+        }
+    }
+
+    /// The whole text. Gets invalidated by every edit.
+    pub fn get(&self, ctx: &RxCtx) -> Cow<'_, str> {
+        self.whole.track(ctx);
+
+        self.text.slice(0..self.text.len())
+    }
+
+    #[track_caller]
+    pub fn get_untracked(&self) -> Cow<'_, str> {
+        crate::hazards::check_untracked_read();
+
+        self.text.slice(0..self.text.len())
+    }
+
+    /// Starts following `range` (in bytes) through the edits.
+    ///
+    /// # Panics
+    ///
+    /// If `range` is out of bounds or doesn't lie on char boundaries.
+    #[track_caller]
+    pub fn range(&self, range: Range<usize>) -> TextRange {
+        self.text.check_range(&range);
+
+        let mut ranges = self.ranges.borrow_mut();
+
+        let anchored = Some(Anchored {
+            range,
+            dependents: Dependents::new(),
+        });
+
+        match ranges.free.pop() {
+            Some(index) => {
+                let slot = &mut ranges.slots[index as usize];
+                slot.anchored = anchored;
+
+                TextRange {
+                    index,
+                    generation: slot.generation,
+                }
+            }
+            None => {
+                let index =
+                    u32::try_from(ranges.slots.len()).expect("RxText can't hold more ranges");
+
+                ranges.slots.push(RangeSlot {
+                    generation: 0,
+                    anchored,
+                });
+
+                TextRange {
+                    index,
+                    generation: 0,
+                }
+            }
+        }
+    }
+
+    /// Stops following the range. Reading it afterwards returns `None`.
+    pub fn remove_range(&self, range: TextRange) {
+        let mut ranges = self.ranges.borrow_mut();
+
+        let Some(slot) = ranges
+            .slots
+            .get_mut(range.index as usize)
+            .filter(|slot| slot.generation == range.generation && slot.anchored.is_some())
+        else {
+            return;
+        };
+
+        slot.anchored = None;
+
+        // A slot whose generation would overflow doesn't get reused, so old ranges stay invalid.
+        if let Some(generation) = slot.generation.checked_add(1) {
+            slot.generation = generation;
+            ranges.free.push(range.index);
+        }
+    }
+
+    /// Where the range currently is, without tracking anything.
+    pub fn bounds(&self, range: TextRange) -> Option<Range<usize>> {
+        self.ranges.borrow().get(range).map(|a| a.range.clone())
+    }
+
+    /// The text in `range`. Only gets invalidated by edits that overlap or touch it.
+    pub fn slice(&self, ctx: &RxCtx, range: TextRange) -> Option<Cow<'_, str>> {
+        let ranges = self.ranges.borrow();
+        let anchored = ranges.get(range)?;
+
+        anchored.dependents.track(ctx);
+
+        Some(self.text.slice(anchored.range.clone()))
+    }
+
+    /// Replaces the text in `range` (in bytes) with `with`.
+    ///
+    /// # Panics
+    ///
+    /// If `range` is out of bounds or doesn't lie on char boundaries.
+    #[track_caller]
+    pub fn replace_range(&mut self, range: Range<usize>, with: &str) {
+        self.text.replace_range(range.clone(), with);
+
+        for anchored in self
+            .ranges
+            .get_mut()
+            .slots
+            .iter_mut()
+            .filter_map(|slot| slot.anchored.as_mut())
+        {
+            let r = &mut anchored.range;
+
+            if r.end < range.start {
+                continue;
+            }
+
+            if r.start > range.end {
+                r.start = r.start + with.len() - range.len();
+                r.end = r.end + with.len() - range.len();
+
+                continue;
+            }
+
+            r.start = r.start.min(range.start);
+            r.end = if r.end >= range.end {
+                r.end + with.len() - range.len()
+            } else {
+                range.start + with.len()
+            };
+
+            anchored.dependents.mark_dirty();
+        }
+
+        self.whole.mark_dirty();
+    }
+
+    #[track_caller]
+    pub fn insert(&mut self, at: usize, text: &str) {
+        self.replace_range(at..at, text);
+    }
+
+    #[track_caller]
+    pub fn delete(&mut self, range: Range<usize>) {
+        self.replace_range(range, "");
+    }
+}
+
+// The text is `buf` without the bytes in `gap`. Both ends of the gap always lie on char
+// boundaries, so the parts before and after it are valid UTF-8 on their own.
+#[derive(Debug, Default)]
+struct GapBuffer {
+    buf: Vec<u8>,
+    gap: Range<usize>,
+}
+
+impl GapBuffer {
+    fn new(text: String) -> Self {
+        let len = text.len();
+
+        GapBuffer {
+            buf: text.into_bytes(),
+            gap: len..len,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.buf.len() - self.gap.len()
+    }
+
+    fn is_char_boundary(&self, at: usize) -> bool {
+        let byte = if at < self.gap.start {
+            self.buf[at]
+        } else if at < self.len() {
+            self.buf[at + self.gap.len()]
+        } else {
+            return at == self.len();
+        };
+
+        // Continuation bytes look like 0b10xxxxxx.
+        (byte as i8) >= -0x40
+    }
+
+    #[track_caller]
+    fn check_range(&self, range: &Range<usize>) {
+        assert!(
+            range.start <= range.end && range.end <= self.len(),
+            "range {range:?} is out of bounds",
+        );
+        assert!(
+            self.is_char_boundary(range.start) && self.is_char_boundary(range.end),
+            "range {range:?} doesn't lie on char boundaries",
+        );
+    }
+
+    fn str(bytes: &[u8]) -> &str {
+        std::str::from_utf8(bytes).expect("the gap splits a char")
+    }
+
+    fn slice(&self, range: Range<usize>) -> Cow<'_, str> {
+        let gap = self.gap.len();
+
+        if range.end <= self.gap.start {
+            Cow::Borrowed(Self::str(&self.buf[range]))
+        } else if range.start >= self.gap.start {
+            Cow::Borrowed(Self::str(&self.buf[range.start + gap..range.end + gap]))
+        } else {
+            let mut text = String::with_capacity(range.len());
+            text.push_str(Self::str(&self.buf[range.start..self.gap.start]));
+            text.push_str(Self::str(&self.buf[self.gap.end..range.end + gap]));
+
+            Cow::Owned(text)
+        }
+    }
+
+    fn move_gap(&mut self, to: usize) {
+        let Range { start, end } = self.gap;
+
+        if to < start {
+            self.buf.copy_within(to..start, end - (start - to));
+            self.gap = to..end - (start - to);
+        } else if to > start {
+            self.buf.copy_within(end..end + (to - start), start);
+            self.gap = to..end + (to - start);
+        }
+    }
+
+    #[track_caller]
+    fn replace_range(&mut self, range: Range<usize>, with: &str) {
+        self.check_range(&range);
+
+        self.move_gap(range.start);
+        self.gap.end += range.len();
+
+        if self.gap.len() < with.len() {
+            // Growing by at least the current size keeps typing amortized constant time.
+            let grow = with.len().max(self.buf.len()).max(16);
+            let end = self.gap.end;
+
+            self.buf.splice(end..end, std::iter::repeat_n(0, grow));
+            self.gap.end += grow;
+        }
+
+        self.buf[self.gap.start..self.gap.start + with.len()].copy_from_slice(with.as_bytes());
+        self.gap.start += with.len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::{Dependent, RxFn};
+
+    #[test]
+    fn test_text() {
+        let mut text = RxText::new("fn a() {}\nfn b() {}");
+        let first = text.range(0..9);
+        let second = text.range(10..19);
+
+        let times_called = Cell::new(0);
+
+        let mut f = RxFn::new();
+        let mut highlight = |ctx, text: &RxText| -> usize {
+            *f.call(ctx, (), |ctx, ()| {
+                times_called.set(times_called.get() + 1);
+                text.slice(ctx, second).unwrap().len()
+            })
+        };
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(highlight(ctx, &text), 9);
+
+        text.insert(4, "bc");
+        assert_eq!(text.slice(ctx, first).as_deref(), Some("fn abc() {}"));
+        assert_eq!(text.slice(ctx, second).as_deref(), Some("fn b() {}"));
+        assert_eq!(highlight(ctx, &text), 9);
+        assert_eq!(times_called.get(), 1);
+
+        text.delete(16..18);
+        assert_eq!(text.bounds(second), Some(12..19));
+        assert_eq!(text.slice(ctx, second).as_deref(), Some("fn b {}"));
+        assert_eq!(highlight(ctx, &text), 7);
+        assert_eq!(times_called.get(), 2);
+    }
+
+    #[test]
+    fn test_edits() {
+        let mut text = RxText::new("hello world");
+        let mut expected = String::from("hello world");
+
+        // moves the gap back and forth, and makes it grow
+        for (range, with) in [
+            (5..5, ","),
+            (12..12, "!"),
+            (0..1, "H"),
+            (7..12, "wörld"),
+            (3..5, ""),
+            (0..0, "Oh, "),
+        ] {
+            text.replace_range(range.clone(), with);
+            expected.replace_range(range, with);
+
+            assert_eq!(text.get_untracked(), expected);
+        }
+
+        let whole = text.range(0..expected.len());
+
+        let dependent = Dependent::toplevel();
+        assert_eq!(
+            text.slice(&dependent.ctx(), whole).as_deref(),
+            Some(&*expected)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "char boundaries")]
+    fn test_range_in_char() {
+        RxText::new("wörld").range(0..2);
+    }
+
+    #[test]
+    fn test_reuse_ranges() {
+        let text = RxText::new("abc");
+
+        let a = text.range(0..1);
+        text.remove_range(a);
+
+        let b = text.range(1..2);
+        assert_eq!(b.index, a.index);
+
+        // the old range doesn't refer to the new one
+        assert_eq!(text.bounds(a), None);
+        assert_eq!(text.bounds(b), Some(1..2));
+
+        text.remove_range(a);
+        assert_eq!(text.bounds(b), Some(1..2));
+    }
+}
@@ -0,0 +1,86 @@
+use std::{borrow::Cow, rc::Rc};
+
+use crate::{Changed, Dependent, Dependents, RxCtx};
+
+/// Reactive state without a value, for external events (like a file changing on disk) that should
+/// invalidate the computations that depend on them.
+#[derive(Debug, Default)]
+pub struct Trigger {
+    dependents: Dependents,
+}
+
+impl Clone for Trigger {
+    fn clone(&self) -> Self {
+        Trigger {
+            dependents: Dependents::with_name(self.dependents.0.name.clone()),
+        }
+    }
+}
+
+impl Trigger {
+    pub fn new() -> Self {
+        Trigger::default()
+    }
+
+    /// The name shows up in the debug output and the graph exported by [`crate::debug::to_dot`].
+    pub fn new_named(name: impl Into<Cow<'static, str>>) -> Self {
+        Trigger {
+            dependents: Dependents::new_named(name),
+        }
+    }
+
+    /// Makes the computation that is currently being evaluated in `ctx` rerun the next time this
+    /// fires.
+    pub fn track(&self, ctx: &RxCtx) {
+        self.dependents.track(ctx);
+    }
+
+    #[track_caller]
+    pub fn fire(&mut self) {
+        self.dependents.mark_dirty();
+    }
+
+    /// Everything that currently depends on this.
+    pub fn dependents(&self) -> Vec<Rc<Dependent>> {
+        self.dependents.dependents()
+    }
+
+    /// Resolves the next time this fires.
+    pub fn changed(&self) -> Changed {
+        self.dependents.changed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::RxFn;
+
+    #[test]
+    fn test_trigger() {
+        let mut trigger = Trigger::new();
+        let times_called = Cell::new(0);
+
+        let mut f = RxFn::new();
+        let mut run = |ctx, trigger: &Trigger| {
+            f.call(ctx, (), |ctx, ()| {
+                trigger.track(ctx);
+                times_called.set(times_called.get() + 1);
+            });
+        };
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        run(ctx, &trigger);
+        run(ctx, &trigger);
+        assert_eq!(times_called.get(), 1);
+
+        trigger.fire();
+
+        run(ctx, &trigger);
+        assert_eq!(times_called.get(), 2);
+    }
+}
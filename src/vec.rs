@@ -0,0 +1,1189 @@
+use std::{
+    cell::{Ref, RefCell},
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    fmt,
+    hash::Hash,
+};
+
+use crate::{Dependents, RxCtx, RxEvents, RxFn, RxHashMap};
+
+/// Identifies an element of an [`RxVec`] for as long as it's in there, no matter where it gets
+/// moved.
+///
+/// Every vec counts its ids up from zero on its own, so they only depend on what happened to that
+/// vec. That keeps things like the debug output of [`RxVec::changes`] the same between test runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VecId(u64);
+
+impl VecId {
+    /// A number that stays the same for as long as the element exists, for storing the id
+    /// somewhere that only takes plain keys.
+    pub fn as_u64(self) -> u64 {
+        self.0
+    }
+}
+
+impl fmt::Display for VecId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// What happened to an element of an [`RxVec`]. See [`RxVec::changes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VecChange {
+    Inserted(VecId),
+    Removed(VecId),
+    /// It's at a different index now, either because it was moved itself or because it was
+    /// swapped with another element.
+    Moved(VecId),
+    /// The value was borrowed mutably.
+    Changed(VecId),
+}
+
+impl VecChange {
+    pub fn id(&self) -> VecId {
+        match *self {
+            VecChange::Inserted(id)
+            | VecChange::Removed(id)
+            | VecChange::Moved(id)
+            | VecChange::Changed(id) => id,
+        }
+    }
+}
+
+/// A reactive vector where every element has its own list of dependents and a [`VecId`].
+///
+/// The length and the order are tracked separately from the elements. Reading the length only gets
+/// invalidated when elements are added or removed, and reading an element by its index only when
+/// the element itself changes or something moves it to a different index, so pushing doesn't
+/// affect it. Because the ids stay the same, derived collections like the one from
+/// [`RxVec::map_keyed`] can keep what they computed for an element.
+#[derive(Debug)]
+pub struct RxVec<T> {
+    items: Vec<Item<T>>,
+    next_id: u64,
+    len: Dependents,
+    // Changes when an element that stays in the vec ends up at a different index.
+    order: Dependents,
+    changes: RxEvents<VecChange>,
+    // Where every element is, for finding them by their ids. It gets built when it's needed and
+    // dropped again when elements are moved or removed.
+    positions: RefCell<Option<HashMap<VecId, usize>>>,
+}
+
+#[derive(Debug)]
+struct Item<T> {
+    id: VecId,
+    value: T,
+    dependents: Dependents,
+}
+
+impl<T> Default for RxVec<T> {
+    fn default() -> Self {
+        RxVec::new()
+    }
+}
+
+impl<T> RxVec<T> {
+    pub fn new() -> Self {
+        RxVec {
+            items: Vec::new(),
+            next_id: 0,
+            len: Dependents::new(),
+            order: Dependents::new(),
+            changes: RxEvents::new(),
+            positions: RefCell::new(None),
+        }
+    }
+
+    /// Only gets invalidated when elements are added or removed.
+    pub fn len(&self, ctx: &RxCtx) -> usize {
+        self.len.track(ctx);
+
+        self.items.len()
+    }
+
+    pub fn is_empty(&self, ctx: &RxCtx) -> bool {
+        self.len(ctx) == 0
+    }
+
+    /// Only gets invalidated when the element at `index` changes or gets replaced by another one.
+    pub fn get(&self, ctx: &RxCtx, index: usize) -> Option<&T> {
+        let Some(item) = self.items.get(index) else {
+            // There's going to be an element once enough get pushed.
+            self.len.track(ctx);
+
+            return None;
+        };
+
+        self.order.track(ctx);
+        item.dependents.track(ctx);
+
+        Some(&item.value)
+    }
+
+    /// Subscribes to the length, the order and every element. To only find out which elements
+    /// there are, use [`RxVec::iter_ids`].
+    pub fn iter(&self, ctx: &RxCtx) -> impl Iterator<Item = (VecId, &T)> {
+        self.len.track(ctx);
+        self.order.track(ctx);
+
+        for item in &self.items {
+            item.dependents.track(ctx);
+        }
+
+        self.items.iter().map(|item| (item.id, &item.value))
+    }
+
+    /// Only gets invalidated when elements are added, removed or moved, not when they change.
+    pub fn iter_ids(&self, ctx: &RxCtx) -> impl Iterator<Item = VecId> + '_ {
+        self.len.track(ctx);
+        self.order.track(ctx);
+
+        self.items.iter().map(|item| item.id)
+    }
+
+    /// Only gets invalidated when this element changes or gets removed, no matter where it gets
+    /// moved.
+    pub fn find_by_id(&self, ctx: &RxCtx, id: VecId) -> Option<&T> {
+        // It's gone for good, because ids never get reused.
+        let index = *self.positions().get(&id)?;
+
+        let item = &self.items[index];
+        item.dependents.track(ctx);
+
+        Some(&item.value)
+    }
+
+    #[track_caller]
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        let item = self.items.get_mut(index)?;
+        item.dependents.mark_dirty();
+        self.changes.emit(VecChange::Changed(item.id));
+
+        Some(&mut item.value)
+    }
+
+    #[track_caller]
+    pub fn push(&mut self, value: T) -> VecId {
+        self.insert(self.items.len(), value)
+    }
+
+    /// # Panics
+    ///
+    /// If `index` is greater than the length.
+    #[track_caller]
+    pub fn insert(&mut self, index: usize, value: T) -> VecId {
+        let id = VecId(self.next_id);
+        self.next_id += 1;
+
+        self.items.insert(
+            index,
+            Item {
+                id,
+                value,
+                dependents: Dependents::new(),
+            },
+        );
+
+        self.len.mark_dirty();
+
+        if index + 1 < self.items.len() {
+            self.reordered();
+        } else if let Some(positions) = self.positions.get_mut() {
+            positions.insert(id, index);
+        }
+
+        self.changes.emit(VecChange::Inserted(id));
+
+        id
+    }
+
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    #[track_caller]
+    pub fn remove(&mut self, index: usize) -> T {
+        let item = self.items.remove(index);
+
+        self.len.mark_dirty();
+
+        if index < self.items.len() {
+            self.reordered();
+        }
+
+        self.removed(item)
+    }
+
+    /// Like [`Vec::swap_remove`], which moves the last element into the gap.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    #[track_caller]
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        let item = self.items.swap_remove(index);
+
+        self.len.mark_dirty();
+
+        if let Some(moved) = self.items.get(index).map(|item| item.id) {
+            self.reordered();
+            self.changes.emit(VecChange::Moved(moved));
+        }
+
+        self.removed(item)
+    }
+
+    /// Swaps two elements, which keep their ids.
+    ///
+    /// # Panics
+    ///
+    /// If either index is out of bounds.
+    #[track_caller]
+    pub fn swap(&mut self, a: usize, b: usize) {
+        self.items.swap(a, b);
+
+        if a != b {
+            self.reordered();
+            self.changes.emit(VecChange::Moved(self.items[a].id));
+            self.changes.emit(VecChange::Moved(self.items[b].id));
+        }
+    }
+
+    /// Moves the element at `from` so that it ends up at `to`, shifting the ones in between. It
+    /// keeps its id, so things like [`RxVec::map_keyed`] see it as moved instead of as removed and
+    /// inserted again.
+    ///
+    /// # Panics
+    ///
+    /// If either index is out of bounds.
+    #[track_caller]
+    pub fn move_item(&mut self, from: usize, to: usize) {
+        assert!(to < self.items.len(), "index out of bounds");
+
+        let item = self.items.remove(from);
+        let id = item.id;
+        self.items.insert(to, item);
+
+        if from != to {
+            self.reordered();
+            self.changes.emit(VecChange::Moved(id));
+        }
+    }
+
+    #[track_caller]
+    pub fn truncate(&mut self, len: usize) {
+        self.retain_items(|index, _| index < len);
+    }
+
+    #[track_caller]
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /// Removes the elements for which `f` returns `false`.
+    #[track_caller]
+    pub fn retain(&mut self, mut f: impl FnMut(&T) -> bool) {
+        self.retain_items(|_, value| f(value));
+    }
+
+    #[track_caller]
+    fn retain_items(&mut self, mut f: impl FnMut(usize, &T) -> bool) {
+        let len = self.items.len();
+        let mut index = 0;
+        let mut removed = false;
+        // Whether an element that stays comes after one that got removed.
+        let mut shifted = false;
+
+        self.items.retain(|item| {
+            let keep = f(index, &item.value);
+            index += 1;
+
+            if keep {
+                shifted |= removed;
+            } else {
+                removed = true;
+                item.dependents.mark_dirty();
+                self.changes.emit(VecChange::Removed(item.id));
+            }
+
+            keep
+        });
+
+        if self.items.len() != len {
+            self.len.mark_dirty();
+            *self.positions.get_mut() = None;
+        }
+
+        if shifted {
+            self.reordered();
+        }
+    }
+
+    #[track_caller]
+    fn removed(&mut self, item: Item<T>) -> T {
+        item.dependents.mark_dirty();
+        self.changes.emit(VecChange::Removed(item.id));
+        *self.positions.get_mut() = None;
+
+        item.value
+    }
+
+    #[track_caller]
+    fn reordered(&mut self) {
+        self.order.mark_dirty();
+        *self.positions.get_mut() = None;
+    }
+
+    /// What happened to the elements since the previous run of the computation that is currently
+    /// being evaluated in `ctx`, in the order it happened. This is `None` if it reads this for the
+    /// first time, in which case it has to start from the current elements.
+    ///
+    /// The changes are kept until every computation that reads them has seen them, like with
+    /// [`RxEvents`].
+    pub fn changes(&self, ctx: &RxCtx) -> Option<&[VecChange]> {
+        self.changes.read_since(ctx)
+    }
+
+    /// Maps every element with `f` and keeps the results in `cache` by [`VecId`]. An element only
+    /// gets mapped again when it changes (or something else `f` read does), so the results for the
+    /// others are reused even if elements were added or moved around.
+    pub fn map_keyed<'c, U>(
+        &self,
+        ctx: &RxCtx,
+        cache: &'c mut MapKeyed<U>,
+        mut f: impl FnMut(&RxCtx, &T) -> U,
+    ) -> Vec<&'c U> {
+        self.len.track(ctx);
+        self.order.track(ctx);
+
+        let ids = self.items.iter().map(|item| item.id);
+
+        if !cache.rows.iter().map(|(id, _)| *id).eq(ids.clone()) {
+            let mut old: HashMap<_, _> = cache.rows.drain(..).collect();
+
+            cache.rows = ids
+                .map(|id| (id, old.remove(&id).unwrap_or_default()))
+                .collect();
+        }
+
+        cache
+            .rows
+            .iter_mut()
+            .zip(&self.items)
+            .map(|((_, row), item)| {
+                row.call(ctx, (), |ctx, ()| {
+                    item.dependents.track(ctx);
+
+                    f(ctx, &item.value)
+                })
+            })
+            .collect()
+    }
+
+    /// Combines all elements with `monoid` and keeps the result up to date in `fold`. When
+    /// elements only change or get pushed, just their paths in a segment tree get combined again,
+    /// which takes O(log n) per element. Anything else rebuilds the tree.
+    pub fn fold_incremental<'f, M>(&self, ctx: &RxCtx, fold: &'f mut Fold<M>) -> &'f M::Value
+    where
+        M: Monoid<Item = T>,
+    {
+        let tree = &mut fold.tree;
+
+        fold.this.call(ctx, (), |ctx, ()| match self.changes(ctx) {
+            Some(changes) if tree.update(&self.items, changes) => {}
+            _ => tree.rebuild(&self.items),
+        });
+
+        fold.tree.root()
+    }
+
+    /// The ids of the elements for which `pred` returns `true`, in order. After the first call,
+    /// `pred` only gets called for the elements that changed.
+    pub fn filter_view<'v>(
+        &self,
+        ctx: &RxCtx,
+        view: &'v mut FilterView,
+        mut pred: impl FnMut(&T) -> bool,
+    ) -> &'v [VecId] {
+        let ids = &mut view.ids;
+
+        view.this.call(ctx, (), |ctx, ()| {
+            let Some(changes) = self.changes(ctx) else {
+                *ids = self
+                    .items
+                    .iter()
+                    .filter(|item| pred(&item.value))
+                    .map(|item| item.id)
+                    .collect();
+
+                return;
+            };
+
+            let positions = self.positions();
+
+            for (id, index) in touched(ids, changes, &positions) {
+                if pred(&self.items[index].value) {
+                    let at = ids.partition_point(|other| positions[other] < index);
+                    ids.insert(at, id);
+                }
+            }
+        });
+
+        &view.ids
+    }
+
+    /// The ids of all elements, sorted by `cmp`. Elements that are equal stay in the same order
+    /// as in here. After the first call, only the elements that changed get sorted in again.
+    pub fn sorted_view<'v>(
+        &self,
+        ctx: &RxCtx,
+        view: &'v mut SortedView,
+        mut cmp: impl FnMut(&T, &T) -> Ordering,
+    ) -> &'v [VecId] {
+        let ids = &mut view.ids;
+
+        view.this.call(ctx, (), |ctx, ()| {
+            let Some(changes) = self.changes(ctx) else {
+                let mut items: Vec<_> = self.items.iter().collect();
+                items.sort_by(|a, b| cmp(&a.value, &b.value));
+
+                *ids = items.into_iter().map(|item| item.id).collect();
+
+                return;
+            };
+
+            let positions = self.positions();
+
+            for (id, index) in touched(ids, changes, &positions) {
+                let at = ids.partition_point(|other| {
+                    let other = positions[other];
+
+                    cmp(&self.items[other].value, &self.items[index].value)
+                        .then(other.cmp(&index))
+                        .is_lt()
+                });
+
+                ids.insert(at, id);
+            }
+        });
+
+        &view.ids
+    }
+
+    /// The ids of the elements grouped by what `key` returns for them, in order. After the first
+    /// call, `key` only gets called for the elements that changed.
+    pub fn group_by<'g, K>(
+        &self,
+        ctx: &RxCtx,
+        groups: &'g mut GroupBy<K>,
+        mut key: impl FnMut(&T) -> K,
+    ) -> &'g HashMap<K, Vec<VecId>>
+    where
+        K: Clone + Eq + Hash,
+    {
+        let GroupBy { groups, keys, this } = groups;
+
+        this.call(ctx, (), |ctx, ()| {
+            let Some(changes) = self.changes(ctx) else {
+                groups.clear();
+                keys.clear();
+
+                for item in &self.items {
+                    let key = key(&item.value);
+
+                    groups.entry(key.clone()).or_default().push(item.id);
+                    keys.insert(item.id, key);
+                }
+
+                return;
+            };
+
+            let positions = self.positions();
+            let touched: HashSet<VecId> = changes.iter().map(VecChange::id).collect();
+
+            // Everything that is mentioned gets taken out first, so that the groups only contain
+            // elements that still exist when the others get put back in.
+            for id in &touched {
+                if let Some(key) = keys.remove(id) {
+                    let group = groups.get_mut(&key).unwrap();
+                    group.retain(|other| other != id);
+
+                    if group.is_empty() {
+                        groups.remove(&key);
+                    }
+                }
+            }
+
+            for id in touched {
+                if let Some(&index) = positions.get(&id) {
+                    let key = key(&self.items[index].value);
+
+                    let group = groups.entry(key.clone()).or_default();
+                    let at = group.partition_point(|other| positions[other] < index);
+                    group.insert(at, id);
+
+                    keys.insert(id, key);
+                }
+            }
+        });
+
+        groups
+    }
+
+    /// Maps every element together with the value in `map` under the key that `key` returns for
+    /// it, like a left join. Like with [`RxVec::map_keyed`], a row only gets computed again if its
+    /// element or the value under its key changes.
+    pub fn join<'c, K, V, U>(
+        &self,
+        ctx: &RxCtx,
+        cache: &'c mut MapKeyed<U>,
+        map: &RxHashMap<K, V>,
+        mut key: impl FnMut(&T) -> K,
+        mut f: impl FnMut(&T, Option<&V>) -> U,
+    ) -> Vec<&'c U>
+    where
+        K: Clone + Eq + Hash,
+    {
+        self.map_keyed(ctx, cache, |ctx, item| f(item, map.get(ctx, &key(item))))
+    }
+
+    fn positions(&self) -> Ref<'_, HashMap<VecId, usize>> {
+        let mut positions = self.positions.borrow_mut();
+
+        if positions.is_none() {
+            *positions = Some(
+                self.items
+                    .iter()
+                    .enumerate()
+                    .map(|(index, item)| (item.id, index))
+                    .collect(),
+            );
+        }
+
+        drop(positions);
+
+        Ref::map(self.positions.borrow(), |positions| {
+            positions.as_ref().unwrap()
+        })
+    }
+}
+
+/// Takes the elements that were mentioned in `changes` out of `ids`, so they can be put back in at
+/// the right place, and returns the ones that still exist together with their positions.
+fn touched(
+    ids: &mut Vec<VecId>,
+    changes: &[VecChange],
+    positions: &HashMap<VecId, usize>,
+) -> Vec<(VecId, usize)> {
+    let touched: HashSet<VecId> = changes.iter().map(VecChange::id).collect();
+
+    ids.retain(|id| !touched.contains(id));
+
+    touched
+        .into_iter()
+        .filter_map(|id| Some((id, *positions.get(&id)?)))
+        .collect()
+}
+
+/// What [`RxVec::map_keyed`] computed for every element.
+#[derive(Debug)]
+pub struct MapKeyed<U> {
+    rows: Vec<(VecId, RxFn<(), U>)>,
+}
+
+impl<U> Default for MapKeyed<U> {
+    fn default() -> Self {
+        MapKeyed::new()
+    }
+}
+
+impl<U> MapKeyed<U> {
+    pub fn new() -> Self {
+        MapKeyed { rows: Vec::new() }
+    }
+}
+
+/// What [`RxVec::filter_view`] keeps up to date.
+#[derive(Debug, Default)]
+pub struct FilterView {
+    ids: Vec<VecId>,
+    this: RxFn<(), ()>,
+}
+
+impl FilterView {
+    pub fn new() -> Self {
+        FilterView::default()
+    }
+}
+
+/// What [`RxVec::sorted_view`] keeps up to date.
+#[derive(Debug, Default)]
+pub struct SortedView {
+    ids: Vec<VecId>,
+    this: RxFn<(), ()>,
+}
+
+impl SortedView {
+    pub fn new() -> Self {
+        SortedView::default()
+    }
+}
+
+/// What [`RxVec::group_by`] keeps up to date.
+#[derive(Debug)]
+pub struct GroupBy<K> {
+    groups: HashMap<K, Vec<VecId>>,
+    keys: HashMap<VecId, K>,
+    this: RxFn<(), ()>,
+}
+
+impl<K> Default for GroupBy<K> {
+    fn default() -> Self {
+        GroupBy::new()
+    }
+}
+
+impl<K> GroupBy<K> {
+    pub fn new() -> Self {
+        GroupBy {
+            groups: HashMap::new(),
+            keys: HashMap::new(),
+            this: RxFn::new(),
+        }
+    }
+}
+
+/// A way of combining values that is associative and has an identity, like a sum, a count or
+/// concatenation. See [`RxVec::fold_incremental`].
+pub trait Monoid {
+    type Item;
+    type Value;
+
+    fn empty(&self) -> Self::Value;
+
+    fn lift(&self, item: &Self::Item) -> Self::Value;
+
+    fn combine(&self, a: &Self::Value, b: &Self::Value) -> Self::Value;
+}
+
+/// What [`RxVec::fold_incremental`] keeps up to date.
+pub struct Fold<M: Monoid> {
+    tree: SegmentTree<M>,
+    this: RxFn<(), ()>,
+}
+
+impl<M> fmt::Debug for Fold<M>
+where
+    M: Monoid,
+    M::Value: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Fold")
+            .field("value", self.tree.root())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<M: Monoid> Fold<M> {
+    pub fn new(monoid: M) -> Self {
+        let tree = SegmentTree {
+            nodes: vec![monoid.empty(), monoid.empty()],
+            len: 0,
+            positions: HashMap::new(),
+            monoid,
+        };
+
+        Fold {
+            tree,
+            this: RxFn::new(),
+        }
+    }
+}
+
+struct SegmentTree<M: Monoid> {
+    monoid: M,
+    // The leaves are the second half, and every other node combines its two children, so the root
+    // is at index 1. The number of leaves is a power of two and the unused ones are empty.
+    nodes: Vec<M::Value>,
+    len: usize,
+    positions: HashMap<VecId, usize>,
+}
+
+impl<M: Monoid> SegmentTree<M> {
+    fn root(&self) -> &M::Value {
+        &self.nodes[1]
+    }
+
+    fn rebuild(&mut self, items: &[Item<M::Item>]) {
+        // Leaving room for as many elements again means pushing stays cheap.
+        let leaves = (2 * items.len()).next_power_of_two();
+
+        self.nodes.clear();
+        self.nodes.resize_with(leaves, || self.monoid.empty());
+        self.nodes
+            .extend(items.iter().map(|item| self.monoid.lift(&item.value)));
+        self.nodes.resize_with(2 * leaves, || self.monoid.empty());
+
+        for node in (1..leaves).rev() {
+            self.nodes[node] = self
+                .monoid
+                .combine(&self.nodes[2 * node], &self.nodes[2 * node + 1]);
+        }
+
+        self.len = items.len();
+        self.positions = items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| (item.id, index))
+            .collect();
+    }
+
+    /// Returns `false` if the changes can't be applied one by one.
+    fn update(&mut self, items: &[Item<M::Item>], changes: &[VecChange]) -> bool {
+        let leaves = self.nodes.len() / 2;
+        let pushed = items.len().saturating_sub(self.len);
+
+        if items.len() > leaves
+            || !changes.iter().all(|change| match change {
+                VecChange::Changed(_) => true,
+                VecChange::Inserted(id) => items[self.len..].iter().any(|item| item.id == *id),
+                _ => false,
+            })
+            || changes
+                .iter()
+                .filter(|change| matches!(change, VecChange::Inserted(_)))
+                .count()
+                != pushed
+        {
+            return false;
+        }
+
+        for (index, item) in items.iter().enumerate().skip(self.len) {
+            self.positions.insert(item.id, index);
+        }
+
+        self.len = items.len();
+
+        for change in changes {
+            let (VecChange::Changed(id) | VecChange::Inserted(id)) = change else {
+                unreachable!();
+            };
+
+            let mut node = leaves + self.positions[id];
+            self.nodes[node] = self.monoid.lift(&items[node - leaves].value);
+
+            while node > 1 {
+                node /= 2;
+                self.nodes[node] = self
+                    .monoid
+                    .combine(&self.nodes[2 * node], &self.nodes[2 * node + 1]);
+            }
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::Dependent;
+
+    #[test]
+    fn test_map_keyed() {
+        let mut vec = RxVec::new();
+        vec.push(1);
+        vec.push(2);
+
+        let mut cache = MapKeyed::new();
+        let times_called = Cell::new(0);
+
+        let mut double = |ctx, vec: &RxVec<u32>| -> Vec<u32> {
+            vec.map_keyed(ctx, &mut cache, |_, x| {
+                times_called.set(times_called.get() + 1);
+                x * 2
+            })
+            .into_iter()
+            .copied()
+            .collect()
+        };
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(double(ctx, &vec), [2, 4]);
+        assert_eq!(times_called.get(), 2);
+
+        *vec.get_mut(1).unwrap() = 3;
+        vec.push(4);
+
+        assert_eq!(double(ctx, &vec), [2, 6, 8]);
+        assert_eq!(times_called.get(), 4);
+    }
+
+    #[test]
+    fn test_reorder() {
+        let mut vec = RxVec::new();
+
+        for c in ["a", "b", "c", "d", "e"] {
+            vec.push(c);
+        }
+
+        let mut cache = MapKeyed::new();
+        let times_called = Cell::new(0);
+
+        let mut upper = |ctx, vec: &RxVec<&str>| -> String {
+            vec.map_keyed(ctx, &mut cache, |_, s| {
+                times_called.set(times_called.get() + 1);
+                s.to_uppercase()
+            })
+            .into_iter()
+            .map(String::as_str)
+            .collect()
+        };
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(upper(ctx, &vec), "ABCDE");
+
+        vec.move_item(0, 4);
+        vec.swap(0, 1);
+        assert_eq!(vec.remove(1), "b");
+        assert_eq!(vec.swap_remove(0), "c");
+        vec.insert(1, "f");
+
+        assert_eq!(upper(ctx, &vec), "AFDE");
+        assert_eq!(times_called.get(), 6);
+
+        vec.retain(|s| *s != "f");
+        assert_eq!(upper(ctx, &vec), "ADE");
+
+        vec.truncate(1);
+        assert_eq!(upper(ctx, &vec), "A");
+
+        vec.clear();
+        assert!(vec.is_empty(ctx));
+        assert_eq!(times_called.get(), 6);
+    }
+
+    #[test]
+    fn test_changes() {
+        let mut vec = RxVec::new();
+        let a = vec.push("a");
+
+        let dependent = Dependent::toplevel();
+
+        assert!(dependent.frame(|ctx| vec.changes(ctx).is_none()));
+
+        let b = vec.push("b");
+        let c = vec.insert(0, "c");
+        *vec.get_mut(1).unwrap() = "d";
+        vec.move_item(0, 2);
+        vec.retain(|s| *s != "b");
+
+        assert_eq!(
+            dependent.frame(|ctx| vec.changes(ctx).unwrap().to_vec()),
+            [
+                VecChange::Inserted(b),
+                VecChange::Inserted(c),
+                VecChange::Changed(a),
+                VecChange::Moved(c),
+                VecChange::Removed(b),
+            ],
+        );
+
+        assert!(dependent.frame(|ctx| vec.changes(ctx).unwrap().is_empty()));
+    }
+
+    struct Concat {
+        lifted: Cell<usize>,
+    }
+
+    impl Monoid for Concat {
+        type Item = &'static str;
+        type Value = String;
+
+        fn empty(&self) -> String {
+            String::new()
+        }
+
+        fn lift(&self, item: &&'static str) -> String {
+            self.lifted.set(self.lifted.get() + 1);
+            item.to_string()
+        }
+
+        fn combine(&self, a: &String, b: &String) -> String {
+            format!("{a}{b}")
+        }
+    }
+
+    #[test]
+    fn test_fold_incremental() {
+        let mut vec = RxVec::new();
+        let mut fold = Fold::new(Concat {
+            lifted: Cell::new(0),
+        });
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(vec.fold_incremental(ctx, &mut fold), "");
+
+        vec.push("a");
+        vec.push("b");
+        assert_eq!(vec.fold_incremental(ctx, &mut fold), "ab");
+
+        *vec.get_mut(0).unwrap() = "c";
+        vec.push("d");
+        vec.push("e");
+        assert_eq!(vec.fold_incremental(ctx, &mut fold), "cbde");
+
+        // only what changed got looked at again
+        assert_eq!(fold.tree.monoid.lifted.get(), 5);
+
+        vec.push("f");
+        vec.move_item(4, 0);
+        assert_eq!(vec.fold_incremental(ctx, &mut fold), "fcbde");
+    }
+
+    #[test]
+    fn test_views() {
+        let mut vec = RxVec::new();
+        let a = vec.push(3);
+        let b = vec.push(2);
+        let c = vec.push(1);
+
+        let mut even = FilterView::new();
+        let mut sorted = SortedView::new();
+        let times_called = Cell::new(0);
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        let mut views = |vec: &RxVec<u32>| {
+            let even = vec
+                .filter_view(ctx, &mut even, |x| {
+                    times_called.set(times_called.get() + 1);
+                    x % 2 == 0
+                })
+                .to_vec();
+
+            (even, vec.sorted_view(ctx, &mut sorted, u32::cmp).to_vec())
+        };
+
+        assert_eq!(views(&vec), (vec![b], vec![c, b, a]));
+        assert_eq!(times_called.get(), 3);
+
+        *vec.get_mut(0).unwrap() = 0;
+        let d = vec.insert(1, 2);
+        vec.swap(2, 3);
+
+        assert_eq!(views(&vec), (vec![a, d, b], vec![a, c, d, b]));
+        assert_eq!(times_called.get(), 7);
+
+        vec.remove(0);
+        assert_eq!(views(&vec), (vec![d, b], vec![c, d, b]));
+        assert_eq!(times_called.get(), 7);
+    }
+
+    #[test]
+    fn test_group_by() {
+        let mut vec = RxVec::new();
+        let a = vec.push(("a", 1));
+        let b = vec.push(("b", 2));
+        let c = vec.push(("c", 1));
+
+        let mut groups = GroupBy::new();
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        let by_group = vec.group_by(ctx, &mut groups, |(_, group)| *group);
+        assert_eq!(by_group[&1], [a, c]);
+        assert_eq!(by_group[&2], [b]);
+
+        vec.get_mut(1).unwrap().1 = 1;
+        vec.remove(2);
+        let d = vec.insert(0, ("d", 3));
+
+        let by_group = vec.group_by(ctx, &mut groups, |(_, group)| *group);
+        assert_eq!(by_group.len(), 2);
+        assert_eq!(by_group[&1], [a, b]);
+        assert_eq!(by_group[&3], [d]);
+    }
+
+    #[test]
+    fn test_join() {
+        let mut vec = RxVec::new();
+        vec.push(("a", 1));
+        vec.push(("b", 2));
+
+        let mut names = RxHashMap::new();
+        names.insert(1, "one");
+
+        let mut cache = MapKeyed::new();
+        let times_called = Cell::new(0);
+
+        let mut join = |ctx, names: &RxHashMap<u32, &'static str>| -> Vec<String> {
+            vec.join(
+                ctx,
+                &mut cache,
+                names,
+                |(_, id)| *id,
+                |(item, _), name| {
+                    times_called.set(times_called.get() + 1);
+                    format!("{item}: {}", name.unwrap_or(&"?"))
+                },
+            )
+            .into_iter()
+            .cloned()
+            .collect()
+        };
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        assert_eq!(join(ctx, &names), ["a: one", "b: ?"]);
+
+        names.insert(2, "two");
+
+        assert_eq!(join(ctx, &names), ["a: one", "b: two"]);
+        assert_eq!(times_called.get(), 3);
+    }
+
+    #[test]
+    fn test_len_and_order() {
+        let mut vec = RxVec::new();
+        vec.push(1);
+        vec.push(2);
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        let mut len = RxFn::new();
+        let mut first = RxFn::new();
+        let times_called = Cell::new(0);
+
+        let mut read = |vec: &RxVec<u32>| {
+            let len = *len.call(ctx, (), |ctx, ()| {
+                times_called.set(times_called.get() + 1);
+                vec.len(ctx)
+            });
+
+            let first = *first.call(ctx, (), |ctx, ()| {
+                times_called.set(times_called.get() + 1);
+                vec.get(ctx, 0).copied()
+            });
+
+            (len, first)
+        };
+
+        assert_eq!(read(&vec), (2, Some(1)));
+
+        // neither changes the length nor the first element
+        *vec.get_mut(1).unwrap() = 3;
+        vec.swap(0, 0);
+        assert_eq!(read(&vec), (2, Some(1)));
+        assert_eq!(times_called.get(), 2);
+
+        vec.push(4);
+        vec.remove(2);
+        assert_eq!(read(&vec), (2, Some(1)));
+        assert_eq!(times_called.get(), 3);
+
+        vec.retain(|x| *x != 3);
+        assert_eq!(read(&vec), (1, Some(1)));
+        assert_eq!(times_called.get(), 4);
+
+        vec.insert(0, 5);
+        assert_eq!(read(&vec), (2, Some(5)));
+        assert_eq!(times_called.get(), 6);
+    }
+
+    #[test]
+    fn test_iter() {
+        let mut vec = RxVec::new();
+        let a = vec.push("a");
+        let b = vec.push("b");
+
+        let dependent = Dependent::toplevel();
+
+        let ids: Vec<_> = dependent.frame(|ctx| vec.iter_ids(ctx).collect());
+        assert_eq!(ids, [a, b]);
+
+        *vec.get_mut(0).unwrap() = "c";
+        assert!(!dependent.dirty());
+
+        let items: Vec<_> = dependent.frame(|ctx| vec.iter(ctx).collect());
+        assert_eq!(items, [(a, &"c"), (b, &"b")]);
+
+        *vec.get_mut(1).unwrap() = "d";
+        assert!(dependent.dirty());
+    }
+
+    #[test]
+    fn test_deterministic_ids() {
+        let mut other = RxVec::new();
+        other.push(0);
+
+        let mut vec = RxVec::new();
+        vec.push(1);
+        vec.insert(0, 2);
+        vec.remove(1);
+
+        let dependent = Dependent::toplevel();
+
+        assert_eq!(
+            format!(
+                "{:?}",
+                dependent.frame(|ctx| vec.iter_ids(ctx).collect::<Vec<_>>())
+            ),
+            "[VecId(1)]",
+        );
+    }
+
+    #[test]
+    fn test_find_by_id() {
+        let mut vec = RxVec::new();
+        let a = vec.push("a");
+        let b = vec.push("b");
+
+        let dependent = Dependent::toplevel();
+        let ctx = &dependent.ctx();
+
+        let mut f = RxFn::new();
+        let times_called = Cell::new(0);
+
+        let mut find = |vec: &RxVec<&'static str>| {
+            *f.call(ctx, (), |ctx, ()| {
+                times_called.set(times_called.get() + 1);
+                vec.find_by_id(ctx, b).copied()
+            })
+        };
+
+        assert_eq!(find(&vec), Some("b"));
+
+        vec.insert(0, "c");
+        vec.move_item(2, 0);
+        *vec.get_mut(2).unwrap() = "d";
+
+        assert_eq!(find(&vec), Some("b"));
+        assert_eq!(times_called.get(), 1);
+
+        vec.retain(|s| *s != "b");
+        assert_eq!(find(&vec), None);
+        assert_eq!(times_called.get(), 2);
+
+        let ids: HashSet<_> = [a, b].into();
+        assert!(ids.contains(&a));
+        assert!(a < b);
+        assert_eq!(b.as_u64(), 1);
+        assert_eq!(b.to_string(), "#1");
+    }
+}
@@ -0,0 +1,171 @@
+use std::{
+    fmt,
+    rc::{Rc, Weak},
+};
+
+use crate::{Dependent, DependentsInner, Rx, RxFn};
+
+/// Calls a callback with the old and the new value when an [`Rx`] changes. Created by
+/// [`Rx::watch`].
+///
+/// There's nothing that would run the callback on its own, so [`Watcher::check`] has to be called
+/// regularly (or from wherever [`Dependent::on_dirty`] of [`Watcher::dependent`] schedules it).
+pub struct Watcher<'a, T> {
+    toplevel: Rc<Dependent>,
+    // Only tracks the value. It is kept outside so that a read that panics doesn't lose it.
+    rx_fn: RxFn<(), ()>,
+    value: T,
+    // For checking that it gets checked against the same Rx.
+    source: Weak<DependentsInner>,
+    callback: Callback<'a, T>,
+}
+
+type Callback<'a, T> = Box<dyn FnMut(&T, &T) + 'a>;
+
+impl<T: fmt::Debug> fmt::Debug for Watcher<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Watcher")
+            .field("rx_fn", &self.rx_fn)
+            .field("value", &self.value)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Clone + PartialEq> Rx<T> {
+    /// Starts watching for changes of the value it has right now. See [`Watcher`].
+    pub fn watch<'a>(&self, callback: impl FnMut(&T, &T) + 'a) -> Watcher<'a, T> {
+        let toplevel = Dependent::toplevel();
+        let mut rx_fn = RxFn::new();
+
+        let mut value = None;
+        rx_fn.call(&toplevel.ctx(), (), |ctx, ()| {
+            value = Some(self.get(ctx).clone());
+        });
+
+        Watcher {
+            toplevel,
+            rx_fn,
+            // The closure always runs on the first call.
+            value: value.unwrap(),
+            source: Rc::downgrade(&self.dependents.0),
+            callback: Box::new(callback),
+        }
+    }
+}
+
+impl<T: Clone + PartialEq> Watcher<'_, T> {
+    /// Calls the callback if the value changed since the last check. Returns whether the callback
+    /// was called.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, if `rx` isn't the one this was created from.
+    pub fn check(&mut self, rx: &Rx<T>) -> bool {
+        debug_assert!(
+            self.source.as_ptr() == Rc::as_ptr(&rx.dependents.0),
+            "Watcher::check was called with a different Rx than the one it watches",
+        );
+
+        let mut new = None;
+        self.rx_fn.call(&self.toplevel.ctx(), (), |ctx, ()| {
+            new = Some(rx.get(ctx).clone());
+        });
+
+        // The closure only runs if the value was mutated.
+        let Some(new) = new else {
+            return false;
+        };
+
+        if self.value == new {
+            return false;
+        }
+
+        let old = std::mem::replace(&mut self.value, new);
+
+        (self.callback)(&old, &self.value);
+
+        true
+    }
+
+    /// Gets invalidated when the value gets mutated. Use [`Dependent::on_dirty`] on this to find
+    /// out when [`Watcher::check`] needs to be called.
+    pub fn dependent(&self) -> &Rc<Dependent> {
+        self.rx_fn.dependent()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::RefCell,
+        panic::{self, AssertUnwindSafe},
+    };
+
+    use crate::Rx;
+
+    #[derive(Debug, PartialEq)]
+    struct Panicky(i32);
+
+    impl Clone for Panicky {
+        fn clone(&self) -> Self {
+            assert!(self.0 >= 0, "can't clone negative values");
+
+            Panicky(self.0)
+        }
+    }
+
+    #[test]
+    fn test_watch() {
+        let changes = RefCell::new(Vec::new());
+
+        let mut a = Rx::new(1);
+        let mut watcher = a.watch(|old, new| changes.borrow_mut().push((*old, *new)));
+
+        assert!(!watcher.check(&a));
+
+        a.set(1);
+        assert!(!watcher.check(&a));
+
+        a.set(2);
+        a.set(3);
+        assert!(watcher.check(&a));
+
+        *a.get_mut() += 1;
+        assert!(watcher.check(&a));
+
+        drop(watcher);
+
+        assert_eq!(*changes.borrow(), [(1, 3), (3, 4)]);
+    }
+
+    #[test]
+    fn test_check_after_panic() {
+        let changes = RefCell::new(Vec::new());
+
+        let mut a = Rx::new(Panicky(1));
+        let mut watcher = a.watch(|old, new| changes.borrow_mut().push((old.0, new.0)));
+
+        a.set(Panicky(-1));
+
+        // cloning the value panics, so the change isn't seen yet
+        let result = panic::catch_unwind(AssertUnwindSafe(|| watcher.check(&a)));
+        assert!(result.is_err());
+
+        a.set(Panicky(2));
+        assert!(watcher.check(&a));
+
+        drop(watcher);
+
+        assert_eq!(*changes.borrow(), [(1, 2)]);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "different Rx")]
+    fn test_check_other_rx() {
+        let a = Rx::new(1);
+        let mut watcher = a.watch(|_, _| {});
+
+        watcher.check(&a.clone());
+    }
+}